@@ -0,0 +1,13 @@
+-- Claims of reviewers on pending entity and page revisions, see `ReviewClaim` in
+-- server/src/review/model.rs. A revision can only be claimed once at a time.
+CREATE TABLE IF NOT EXISTS review_claim (
+  revision_id INT NOT NULL,
+  user_id INT NOT NULL,
+  date TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  expires_at TIMESTAMP NOT NULL,
+  PRIMARY KEY (revision_id),
+  KEY review_claim_user_id (user_id),
+  KEY review_claim_expires_at (expires_at),
+  CONSTRAINT review_claim_revision_id FOREIGN KEY (revision_id) REFERENCES uuid (id) ON DELETE CASCADE,
+  CONSTRAINT review_claim_user_id FOREIGN KEY (user_id) REFERENCES user (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
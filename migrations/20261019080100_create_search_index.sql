@@ -0,0 +1,15 @@
+-- Plain text of the current revisions of entities and pages for the full-text search, see
+-- `SearchIndex` in server/src/search/model.rs. The table is filled by `SearchIndexRebuildMutation`
+-- and updated whenever a revision is checked out.
+CREATE TABLE IF NOT EXISTS search_index (
+  uuid_id INT NOT NULL,
+  instance_id INT NOT NULL,
+  typename VARCHAR(255) NOT NULL,
+  title TEXT NOT NULL,
+  meta_description TEXT NOT NULL,
+  text MEDIUMTEXT NOT NULL,
+  PRIMARY KEY (uuid_id),
+  KEY search_index_instance_id (instance_id),
+  FULLTEXT KEY search_index_fulltext (title, meta_description, text),
+  CONSTRAINT search_index_uuid_id FOREIGN KEY (uuid_id) REFERENCES uuid (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
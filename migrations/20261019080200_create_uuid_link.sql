@@ -0,0 +1,11 @@
+-- Internal links in the current revisions of entities and pages, see `Backlink` in
+-- server/src/backlink/model.rs. `target_id` has no foreign key since links to deleted uuids are
+-- reported as broken links.
+CREATE TABLE IF NOT EXISTS uuid_link (
+  source_id INT NOT NULL,
+  target_id INT NOT NULL,
+  path VARCHAR(768) NOT NULL,
+  PRIMARY KEY (source_id, path),
+  KEY uuid_link_target_id (target_id),
+  CONSTRAINT uuid_link_source_id FOREIGN KEY (source_id) REFERENCES uuid (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,13 @@
+-- Private drafts of users for entities and pages, see `Draft` in server/src/draft/model.rs. A
+-- user has at most one draft per repository. `fields` holds the fields as JSON object.
+CREATE TABLE IF NOT EXISTS draft (
+  user_id INT NOT NULL,
+  repository_id INT NOT NULL,
+  fields LONGTEXT NOT NULL,
+  date_created TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  date_updated TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (user_id, repository_id),
+  KEY draft_repository_id (repository_id),
+  CONSTRAINT draft_user_id FOREIGN KEY (user_id) REFERENCES user (id) ON DELETE CASCADE,
+  CONSTRAINT draft_repository_id FOREIGN KEY (repository_id) REFERENCES uuid (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,36 @@
+-- Every user can subscribe to a uuid only once. `Subscription::save` in
+-- server/src/subscription/model.rs and merging taxonomy terms and moving threads rely on this key
+-- to skip subscriptions which already exist.
+
+-- Duplicates are merged into the earliest subscription of a user, which receives emails when
+-- any of the merged subscriptions did.
+UPDATE subscription original
+JOIN (
+  SELECT MIN(id) AS id, MAX(notify_mailman) AS notify_mailman
+  FROM subscription
+  GROUP BY uuid_id, user_id
+  HAVING COUNT(*) > 1
+) merged ON merged.id = original.id
+SET original.notify_mailman = merged.notify_mailman;
+
+DELETE duplicate
+FROM subscription duplicate
+JOIN subscription original
+  ON original.uuid_id = duplicate.uuid_id
+  AND original.user_id = duplicate.user_id
+  AND original.id < duplicate.id;
+
+SET @statement = IF(
+  (
+    SELECT COUNT(*)
+    FROM information_schema.statistics
+    WHERE table_schema = DATABASE()
+      AND table_name = 'subscription'
+      AND index_name = 'subscription_uuid_id_user_id'
+  ) = 0,
+  'ALTER TABLE subscription ADD UNIQUE KEY subscription_uuid_id_user_id (uuid_id, user_id)',
+  'DO 0'
+);
+PREPARE add_unique_key FROM @statement;
+EXECUTE add_unique_key;
+DEALLOCATE PREPARE add_unique_key;
@@ -0,0 +1,87 @@
+-- Taxonomy terms with a special role when content is mapped to subjects, see
+-- `SubjectRegistryEntry` in server/src/subject/model.rs. The table is seeded with the subjects,
+-- AMB subjects and meta images which were hard-coded before.
+CREATE TABLE IF NOT EXISTS subject_registry (
+  term_taxonomy_id INT NOT NULL,
+  kind VARCHAR(32) NOT NULL,
+  amb_subjects JSON,
+  thumbnail VARCHAR(255),
+  PRIMARY KEY (term_taxonomy_id),
+  KEY subject_registry_kind (kind),
+  CONSTRAINT subject_registry_term_taxonomy_id FOREIGN KEY (term_taxonomy_id) REFERENCES term_taxonomy (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
+
+INSERT IGNORE INTO subject_registry (term_taxonomy_id, kind, amb_subjects, thumbnail) VALUES
+  -- Mathematik
+  (5, 'subject', '["http://w3id.org/kim/schulfaecher/s1017"]', 'mathe.png'),
+  (23593, 'subject', '["http://w3id.org/kim/schulfaecher/s1017"]', NULL),
+  (141587, 'subject', '["http://w3id.org/kim/schulfaecher/s1017"]', NULL),
+  (169580, 'subject', '["http://w3id.org/kim/schulfaecher/s1017"]', NULL),
+  -- Nachhaltigkeit
+  (17744, 'subject', '["http://w3id.org/kim/schulfaecher/s1001", "http://w3id.org/kim/schulfaecher/s1008"]', 'nachhaltigkeit.png'),
+  (48416, 'subject', '["http://w3id.org/kim/schulfaecher/s1001", "http://w3id.org/kim/schulfaecher/s1008"]', NULL),
+  (242851, 'subject', '["http://w3id.org/kim/schulfaecher/s1001", "http://w3id.org/kim/schulfaecher/s1008"]', NULL),
+  -- Chemie
+  (18230, 'subject', '["http://w3id.org/kim/schulfaecher/s1002"]', 'chemie.png'),
+  -- Biologie
+  (23362, 'subject', '["http://w3id.org/kim/schulfaecher/s1001"]', 'biologie.png'),
+  -- Englisch
+  (25979, 'subject', '["http://w3id.org/kim/schulfaecher/s1007"]', NULL),
+  (107557, 'subject', '["http://w3id.org/kim/schulfaecher/s1007"]', NULL),
+  (113127, 'subject', '["http://w3id.org/kim/schulfaecher/s1007"]', NULL),
+  -- Latein
+  (33894, 'subject', '["http://w3id.org/kim/schulfaecher/s1016"]', NULL),
+  (106085, 'subject', '["http://w3id.org/kim/schulfaecher/s1016"]', NULL),
+  -- Physik
+  (41107, 'subject', '["http://w3id.org/kim/schulfaecher/s1022"]', NULL),
+  -- Informatik
+  (47899, 'subject', '["http://w3id.org/kim/schulfaecher/s1013"]', 'informatik.png'),
+  -- Politik
+  (79159, 'subject', '["http://w3id.org/kim/schulfaecher/s1023", "http://w3id.org/kim/schulfaecher/s1028"]', NULL),
+  (107556, 'subject', '["http://w3id.org/kim/schulfaecher/s1023", "http://w3id.org/kim/schulfaecher/s1028"]', NULL),
+  -- Medienbildung
+  (106083, 'subject', '["http://w3id.org/kim/schulfaecher/s1046", "http://w3id.org/kim/schulfaecher/s1013"]', NULL),
+  -- Geografie
+  (106084, 'subject', '["http://w3id.org/kim/schulfaecher/s1010"]', NULL),
+  -- Psychologie
+  (106086, 'subject', '["http://w3id.org/kim/schulfaecher/s1043"]', NULL),
+  -- Deutsch als Zweitsprache
+  (112723, 'subject', '["http://w3id.org/kim/schulfaecher/s1006"]', NULL),
+  -- Geschichte
+  (136362, 'subject', '["http://w3id.org/kim/schulfaecher/s1011"]', NULL),
+  (140528, 'subject', '["http://w3id.org/kim/schulfaecher/s1011"]', NULL),
+  -- Wirtschaftskunde
+  (137757, 'subject', '["http://w3id.org/kim/schulfaecher/s1033"]', NULL),
+  -- Musik
+  (167849, 'subject', '["http://w3id.org/kim/schulfaecher/s1020"]', NULL),
+  (48415, 'subject', '["http://w3id.org/kim/schulfaecher/s1020"]', NULL),
+  -- Spanisch
+  (190109, 'subject', '["http://w3id.org/kim/schulfaecher/s1030"]', NULL),
+  -- Italienisch
+  (198076, 'subject', '["http://w3id.org/kim/schulfaecher/s1014"]', NULL),
+  -- Religionen, deren Wahrnehmung und Vorurteile
+  (208736, 'subject', '["http://w3id.org/kim/schulfaecher/s1008", "http://w3id.org/kim/schulfaecher/s1011"]', NULL),
+  -- Deutsch
+  (210462, 'subject', '["http://w3id.org/kim/schulfaecher/s1005"]', NULL),
+  -- Französisch
+  (227992, 'subject', '["http://w3id.org/kim/schulfaecher/s1009"]', NULL),
+  -- Sex Education
+  (78339, 'subject', '["http://w3id.org/kim/schulfaecher/s1029", "http://w3id.org/kim/schulfaecher/s1001"]', NULL),
+  -- Materialwissenschaft
+  (141607, 'subject', '["https://w3id.org/kim/hochschulfaechersystematik/n294"]', NULL),
+  -- Grammatik
+  (140527, 'subject', '["https://w3id.org/kim/hochschulfaechersystematik/n187"]', NULL),
+  -- Kommunikation
+  (173235, 'subject', '["http://w3id.org/kim/schulfaecher/s1043", "http://w3id.org/kim/schulfaecher/s1005"]', NULL),
+  -- Containers whose children are subjects
+  (106081, 'in-construction', '[]', NULL),
+  (146728, 'partner-root', '[]', NULL),
+  -- Content below the children of this term is not attributed to a subject
+  (87993, 'detached', '[]', NULL),
+  -- Test areas
+  (75211, 'excluded', '[]', NULL),
+  (105140, 'excluded', '[]', NULL),
+  (107772, 'excluded', '[]', NULL),
+  (135390, 'excluded', '[]', NULL),
+  (25107, 'excluded', '[]', NULL),
+  (106082, 'excluded', '[]', NULL);
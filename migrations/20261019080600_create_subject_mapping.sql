@@ -0,0 +1,35 @@
+-- Maps every taxonomy term below a subject to this subject, based on the entries of the subject
+-- registry (see `migrations/20261019080500_create_subject_registry.sql`). `excluded` is set for
+-- terms in test areas like "Baustelle" or "Testbereich" and for all terms below them.
+CREATE OR REPLACE VIEW subject_mapping AS
+WITH RECURSIVE mapping AS (
+  SELECT
+    subject.id AS term_taxonomy_id,
+    subject.id AS subject_id,
+    0 AS excluded
+  FROM term_taxonomy root
+  JOIN term_taxonomy subject ON subject.parent_id = root.id
+  WHERE root.parent_id IS NULL
+  OR root.id IN (
+    SELECT term_taxonomy_id FROM subject_registry
+    WHERE kind IN ('in-construction', 'partner-root')
+  )
+
+  UNION
+
+  SELECT
+    child.id,
+    mapping.subject_id,
+    mapping.excluded OR child.id IN (
+      SELECT term_taxonomy_id FROM subject_registry WHERE kind = 'excluded'
+    )
+  FROM term_taxonomy child
+  JOIN mapping ON mapping.term_taxonomy_id = child.parent_id
+  -- "Fächer im Aufbau" is on the level of the normal subjects, so its children are subjects.
+  -- "Partner" is below the subject "Mathematik", but its children are subjects of their own.
+  WHERE child.parent_id NOT IN (
+    SELECT term_taxonomy_id FROM subject_registry
+    WHERE kind IN ('in-construction', 'partner-root', 'detached')
+  )
+)
+SELECT term_taxonomy_id, subject_id, excluded FROM mapping;
@@ -0,0 +1,5 @@
+-- Event type of `SetPageMetadataEventPayload` in server/src/event/model/set_page_metadata.rs
+INSERT INTO event (name, description)
+  SELECT 'page/metadata/set', 'The license, instance, discussion setting or forum of a page was changed'
+  FROM DUAL
+  WHERE NOT EXISTS (SELECT 1 FROM event WHERE name = 'page/metadata/set');
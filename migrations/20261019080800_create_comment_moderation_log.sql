@@ -0,0 +1,15 @@
+-- Moderation of comments, see `ModerationLogEntry` in server/src/moderation/model.rs. A user can
+-- take every action on a comment only once, `reason` is only set for flags.
+CREATE TABLE IF NOT EXISTS comment_moderation_log (
+  id INT NOT NULL AUTO_INCREMENT,
+  comment_id INT NOT NULL,
+  actor_id INT NOT NULL,
+  action VARCHAR(32) NOT NULL,
+  reason VARCHAR(32),
+  date TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (id),
+  UNIQUE KEY comment_moderation_log_comment_id_actor_id_action (comment_id, actor_id, action),
+  KEY comment_moderation_log_actor_id (actor_id),
+  CONSTRAINT comment_moderation_log_comment_id FOREIGN KEY (comment_id) REFERENCES comment (id) ON DELETE CASCADE,
+  CONSTRAINT comment_moderation_log_actor_id FOREIGN KEY (actor_id) REFERENCES user (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,14 @@
+-- Prior versions of the content of comments, see `CommentHistoryEntry` in
+-- server/src/uuid/model/comment.rs. A new entry is added whenever a comment is edited.
+CREATE TABLE IF NOT EXISTS comment_history (
+  id INT NOT NULL AUTO_INCREMENT,
+  comment_id INT NOT NULL,
+  content LONGTEXT NOT NULL,
+  editor_id INT NOT NULL,
+  date TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (id),
+  KEY comment_history_comment_id_date (comment_id, date),
+  KEY comment_history_editor_id (editor_id),
+  CONSTRAINT comment_history_comment_id FOREIGN KEY (comment_id) REFERENCES comment (id) ON DELETE CASCADE,
+  CONSTRAINT comment_history_editor_id FOREIGN KEY (editor_id) REFERENCES user (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,9 @@
+-- Users mentioned with `@username` in comments, see `Mention` in server/src/uuid/model/comment.rs
+CREATE TABLE IF NOT EXISTS comment_mention (
+  comment_id INT NOT NULL,
+  user_id INT NOT NULL,
+  PRIMARY KEY (comment_id, user_id),
+  KEY comment_mention_user_id (user_id),
+  CONSTRAINT comment_mention_comment_id FOREIGN KEY (comment_id) REFERENCES comment (id) ON DELETE CASCADE,
+  CONSTRAINT comment_mention_user_id FOREIGN KEY (user_id) REFERENCES user (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,15 @@
+-- Event type of `MoveThreadEventPayload` in server/src/event/model/move_thread.rs
+INSERT INTO event (name, description)
+  SELECT 'discussion/move', 'A thread was moved to another object'
+  FROM DUAL
+  WHERE NOT EXISTS (SELECT 1 FROM event WHERE name = 'discussion/move');
+
+INSERT INTO event_parameter_name (name)
+  SELECT 'from'
+  FROM DUAL
+  WHERE NOT EXISTS (SELECT 1 FROM event_parameter_name WHERE name = 'from');
+
+INSERT INTO event_parameter_name (name)
+  SELECT 'to'
+  FROM DUAL
+  WHERE NOT EXISTS (SELECT 1 FROM event_parameter_name WHERE name = 'to');
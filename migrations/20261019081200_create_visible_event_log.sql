@@ -0,0 +1,26 @@
+-- Event logs which are shown in notifications, see `Notifications::fetch` and
+-- `Notifications::count_unread` in server/src/notification.rs. Events of attachments, blog posts
+-- and legacy entity types are hidden. An event log appears once for every parameter and is
+-- visible when one of these rows is. The view has no aggregation, so MySQL merges it into the
+-- queries using it.
+CREATE OR REPLACE ALGORITHM = MERGE VIEW visible_event_log AS
+SELECT event_log.id, event_log.event_id
+FROM event_log
+JOIN uuid uuid1 ON uuid1.id = event_log.uuid_id
+LEFT JOIN entity entity1 ON entity1.id = event_log.uuid_id
+LEFT JOIN type type1 ON type1.id = entity1.type_id
+LEFT JOIN event_parameter ON event_parameter.log_id = event_log.id
+LEFT JOIN event_parameter_uuid ON event_parameter_uuid.event_parameter_id = event_parameter.id
+LEFT JOIN event_parameter_string ON event_parameter_string.event_parameter_id = event_parameter.id
+LEFT JOIN uuid uuid2 ON uuid2.id = event_parameter_uuid.uuid_id
+LEFT JOIN entity entity2 ON entity2.id = event_parameter_uuid.uuid_id
+LEFT JOIN type type2 ON type2.id = entity2.type_id
+WHERE event_parameter_string.value IS NULL
+  AND uuid1.discriminator NOT IN ('attachment', 'blogPost')
+  AND (uuid2.discriminator IS NULL OR uuid2.discriminator NOT IN ('attachment', 'blogPost'))
+  AND (type1.name IS NULL
+    OR type1.name IN ('text-exercise', 'article', 'text-exercise-group', 'video', 'course',
+                      'course-page', 'applet', 'event'))
+  AND (type2.name IS NULL
+    OR type2.name IN ('text-exercise', 'article', 'text-exercise-group', 'video', 'course',
+                      'course-page', 'applet', 'event'));
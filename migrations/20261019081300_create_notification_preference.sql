@@ -0,0 +1,10 @@
+-- How users want to be notified about events of a type, see `NotificationPreference` in
+-- server/src/notification.rs. A user has at most one preference per event type.
+CREATE TABLE IF NOT EXISTS notification_preference (
+  user_id INT NOT NULL,
+  event_type VARCHAR(64) NOT NULL,
+  delivery VARCHAR(32) NOT NULL,
+  PRIMARY KEY (user_id, event_type),
+  KEY notification_preference_event_type (event_type),
+  CONSTRAINT notification_preference_user_id FOREIGN KEY (user_id) REFERENCES user (id) ON DELETE CASCADE
+) ENGINE = InnoDB DEFAULT CHARSET = utf8mb4;
@@ -0,0 +1,15 @@
+-- Date of the last edit of a comment, set by `ThreadEditCommentMutation` in server/src/thread.rs
+SET @statement = IF(
+  (
+    SELECT COUNT(*)
+    FROM information_schema.columns
+    WHERE table_schema = DATABASE()
+      AND table_name = 'comment'
+      AND column_name = 'edit_date'
+  ) = 0,
+  'ALTER TABLE comment ADD COLUMN edit_date TIMESTAMP NULL DEFAULT NULL',
+  'DO 0'
+);
+PREPARE add_edit_date FROM @statement;
+EXECUTE add_edit_date;
+DEALLOCATE PREPARE add_edit_date;
@@ -0,0 +1,14 @@
+-- Parameters of `SetPageMetadataEventPayload` in server/src/event/model/set_page_metadata.rs,
+-- which store the settings of a page before and after the change
+INSERT INTO event_parameter_name (name)
+  SELECT name
+  FROM (
+    SELECT 'previous_instance' AS name
+    UNION ALL SELECT 'previous_discussions_enabled'
+    UNION ALL SELECT 'discussions_enabled'
+    UNION ALL SELECT 'previous_forum'
+    UNION ALL SELECT 'forum'
+  ) parameter
+  WHERE NOT EXISTS (
+    SELECT 1 FROM event_parameter_name WHERE event_parameter_name.name = parameter.name
+  );
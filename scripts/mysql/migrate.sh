@@ -0,0 +1,11 @@
+#!/bin/sh
+
+# Applies the schema migrations in `migrations/` in the order of their file names. All migrations
+# are idempotent, so the script can be executed again after new migrations have been added.
+
+set -e
+
+for migration in migrations/*.sql; do
+  echo "🟢 Apply migration $migration"
+  docker compose exec -T mysql serlo-mysql < "$migration"
+done
@@ -29,7 +29,7 @@ function main() {
     test_no_uncommitted_changes_when_pushing
   fi
 
-  print_header "Check sqlx-data.json is up to date"
+  print_header "Check the sqlx query cache in .sqlx is up to date"
   test_sqlx_data_up_to_date
 
   print_header "Run linter"
@@ -48,8 +48,8 @@ function test_no_uncommitted_changes_when_pushing() {
 function test_sqlx_data_up_to_date() {
   yarn sqlx:prepare
 
-  if [ -n "$(git diff sqlx-data.json)" ]; then
-    error "You need to run sqlx:prepare and commit the changes in sqlx-data.json!"
+  if [ -n "$(git status --porcelain .sqlx)" ]; then
+    error "You need to run sqlx:prepare and commit the changes in .sqlx!"
   fi
 }
 
@@ -3,7 +3,7 @@ use regex::Regex;
 use crate::alias::messages::alias_query;
 use crate::instance::Instance;
 use crate::operation;
-use crate::uuid::{Uuid, UuidFetcher};
+use crate::uuid::{ConcreteUuid, Uuid, UuidFetcher};
 
 type Alias = alias_query::Output;
 
@@ -69,7 +69,9 @@ pub async fn fetch<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
 
     let mut transaction = acquire_from.begin().await?;
 
-    let id = if let Some(captures) = re.captures(path) {
+    let mut is_id_path = false;
+
+    let mut id = if let Some(captures) = re.captures(path) {
         let username = captures.name("username").unwrap().as_str();
         sqlx::query!(
             r#"
@@ -86,6 +88,7 @@ pub async fn fetch<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
     } else {
         let re = Regex::new(r"^(?P<subject>[^/]+/)?(?P<id>\d+)/(?P<title>[^/]*)$").unwrap();
         if let Some(captures) = re.captures(path) {
+            is_id_path = true;
             captures.name("id").unwrap().as_str().parse().unwrap()
         } else {
             sqlx::query!(
@@ -105,7 +108,29 @@ pub async fn fetch<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
         }
     };
 
-    let uuid = Uuid::fetch(id, &mut *transaction).await?;
+    let mut uuid = Uuid::fetch(id, &mut *transaction).await?;
+
+    // Aliases of merged taxonomy terms redirect to the term they were merged into. Merged terms
+    // are trashed, so the redirect is only looked up when the path points to a trashed term.
+    if is_id_path && uuid.trashed && matches!(uuid.concrete_uuid, ConcreteUuid::TaxonomyTerm(_)) {
+        if let Some(result) = sqlx::query!(
+            r#"
+                SELECT a.uuid_id FROM url_alias a
+                    JOIN instance i on i.id = a.instance_id
+                    WHERE i.subdomain = ? AND a.alias = ? AND a.uuid_id != ?
+                    ORDER BY a.timestamp DESC
+            "#,
+            instance,
+            path,
+            id
+        )
+        .fetch_optional(&mut *transaction)
+        .await?
+        {
+            id = result.uuid_id as i32;
+            uuid = Uuid::fetch(id, &mut *transaction).await?;
+        }
+    }
 
     Ok(alias_query::Output {
         id,
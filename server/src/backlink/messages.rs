@@ -0,0 +1,142 @@
+use actix_web::HttpResponse;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::model::Backlink;
+use crate::message::MessageResponder;
+use crate::operation::{self, Operation};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum BacklinkMessage {
+    BacklinksQuery(backlinks_query::Payload),
+    BrokenLinksQuery(broken_links_query::Payload),
+    BacklinksRebuildMutation(backlinks_rebuild_mutation::Payload),
+}
+
+#[async_trait]
+impl MessageResponder for BacklinkMessage {
+    async fn handle<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> HttpResponse {
+        match self {
+            BacklinkMessage::BacklinksQuery(payload) => payload.handle(acquire_from).await,
+            BacklinkMessage::BrokenLinksQuery(payload) => payload.handle(acquire_from).await,
+            BacklinkMessage::BacklinksRebuildMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+        }
+    }
+}
+
+pub mod backlinks_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub id: i32,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Link {
+        pub source_id: i32,
+        #[serde(rename = "__typename")]
+        pub typename: String,
+        pub path: String,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub backlinks: Vec<Link>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Backlink::fetch_backlinks(self.id, acquire_from).await
+        }
+    }
+}
+
+pub mod broken_links_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub after: Option<i32>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct BrokenLink {
+        pub source_id: i32,
+        pub target_id: i32,
+        pub path: String,
+        pub reason: String,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub broken_links: Vec<BrokenLink>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            Backlink::fetch_broken_links(self, acquire_from).await
+        }
+    }
+}
+
+pub mod backlinks_rebuild_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub after: Option<i32>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub success: bool,
+        pub indexed_ids: Vec<i32>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 1_000)?;
+
+            Backlink::rebuild(self, acquire_from).await
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub use messages::BacklinkMessage;
+pub use model::*;
+
+mod messages;
+mod model;
@@ -0,0 +1,381 @@
+use regex::Regex;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use super::messages::{backlinks_query, backlinks_rebuild_mutation, broken_links_query};
+use crate::operation;
+use crate::uuid::EntityType;
+
+/// Links between the current revisions of entities and pages and the uuids they point to.
+///
+/// The links are stored in the `uuid_link` table with the columns `source_id`, `target_id` and
+/// `path` (primary key is (`source_id`, `path`)). Only links which resolve to a uuid id (either
+/// via a path like `/12345` or via an alias) are stored. Entries are updated whenever a revision
+/// is checked out; [`Backlink::rebuild`] fills the table for existing content.
+pub struct Backlink;
+
+impl Backlink {
+    pub async fn update<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        source_id: i32,
+        acquire_from: A,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        // Only the fields with editor content are scanned, each of them on its own since the
+        // content needs to be parsed as editor state.
+        let entity_fields = sqlx::query!(
+            r#"
+                SELECT e.instance_id, f.value AS "content?"
+                FROM entity e
+                JOIN type ON type.id = e.type_id
+                LEFT JOIN entity_revision_field f
+                    ON f.entity_revision_id = e.current_revision_id
+                    AND f.field = IF(type.name = "video", "description", "content")
+                WHERE e.id = ?
+                    AND e.current_revision_id IS NOT NULL
+            "#,
+            source_id
+        )
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let source = match entity_fields.first() {
+            Some(entity) => Some((
+                entity.instance_id as i32,
+                entity_fields
+                    .iter()
+                    .filter_map(|field| field.content.clone())
+                    .collect(),
+            )),
+            None => sqlx::query!(
+                r#"
+                    SELECT p.instance_id, r.content
+                    FROM page_repository p
+                    JOIN page_revision r ON r.id = p.current_revision_id
+                    WHERE p.id = ?
+                "#,
+                source_id
+            )
+            .fetch_optional(&mut *transaction)
+            .await?
+            .map(|page| (page.instance_id as i32, vec![page.content])),
+        };
+
+        sqlx::query!(r#"DELETE FROM uuid_link WHERE source_id = ?"#, source_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        if let Some((instance_id, contents)) = source {
+            let mut paths: Vec<String> = Vec::new();
+            for path in contents
+                .iter()
+                .flat_map(|content| extract_link_paths(content))
+            {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+
+            for path in paths {
+                let target_id = match uuid_id_from_path(&path) {
+                    Some(id) => Some(id),
+                    None => sqlx::query!(
+                        r#"
+                            SELECT uuid_id FROM url_alias
+                                WHERE instance_id = ? AND alias = ?
+                                ORDER BY timestamp DESC
+                                LIMIT 1
+                        "#,
+                        instance_id,
+                        path.trim_start_matches('/')
+                    )
+                    .fetch_optional(&mut *transaction)
+                    .await?
+                    .map(|alias| alias.uuid_id as i32),
+                };
+
+                if let Some(target_id) = target_id.filter(|target_id| *target_id != source_id) {
+                    sqlx::query!(
+                        r#"
+                            INSERT INTO uuid_link (source_id, target_id, path)
+                                VALUES (?, ?, ?)
+                        "#,
+                        source_id,
+                        target_id,
+                        path,
+                    )
+                    .execute(&mut *transaction)
+                    .await?;
+                }
+            }
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn rebuild<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &backlinks_rebuild_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<backlinks_rebuild_mutation::Output, operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        let mut ids: Vec<i32> = sqlx::query!(
+            r#"
+                SELECT sources.id
+                FROM (
+                    SELECT e.id
+                    FROM entity e
+                    WHERE e.current_revision_id IS NOT NULL
+                    UNION
+                    SELECT p.id
+                    FROM page_repository p
+                    WHERE p.current_revision_id IS NOT NULL
+                ) sources
+                WHERE (? IS NULL OR sources.id > ?)
+                ORDER BY sources.id
+                LIMIT ?
+            "#,
+            payload.after,
+            payload.after,
+            payload.first + 1,
+        )
+        .fetch_all(&mut *transaction)
+        .await?
+        .into_iter()
+        .map(|record| record.id as i32)
+        .collect();
+
+        let has_next_page = ids.len() as i32 > payload.first;
+        ids.truncate(payload.first as usize);
+
+        for id in &ids {
+            Self::update(*id, &mut *transaction).await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(backlinks_rebuild_mutation::Output {
+            success: true,
+            indexed_ids: ids,
+            has_next_page,
+        })
+    }
+
+    pub async fn fetch_backlinks<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        id: i32,
+        acquire_from: A,
+    ) -> Result<backlinks_query::Output, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+
+        let backlinks = sqlx::query!(
+            r#"
+                SELECT l.source_id, l.path, type.name AS "entity_type?"
+                FROM uuid_link l
+                JOIN uuid source ON source.id = l.source_id
+                LEFT JOIN entity e ON e.id = l.source_id
+                LEFT JOIN type ON type.id = e.type_id
+                WHERE l.target_id = ?
+                    AND source.trashed = 0
+                ORDER BY l.source_id, l.path
+            "#,
+            id
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|record| backlinks_query::Link {
+            source_id: record.source_id as i32,
+            typename: to_typename(record.entity_type),
+            path: record.path,
+        })
+        .collect();
+
+        Ok(backlinks_query::Output { backlinks })
+    }
+
+    pub async fn fetch_broken_links<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &broken_links_query::Payload,
+        acquire_from: A,
+    ) -> Result<broken_links_query::Output, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+
+        // Pagination happens per source so that all broken links of one source are on the same
+        // page.
+        let records = sqlx::query!(
+            r#"
+                SELECT
+                    l.source_id,
+                    l.target_id,
+                    l.path,
+                    target.id IS NULL AS "missing: bool"
+                FROM uuid_link l
+                JOIN (
+                    SELECT DISTINCT l.source_id
+                    FROM uuid_link l
+                    JOIN uuid source ON source.id = l.source_id
+                    LEFT JOIN uuid target ON target.id = l.target_id
+                    WHERE source.trashed = 0
+                        AND (target.id IS NULL OR target.trashed = 1)
+                        AND (? IS NULL OR l.source_id > ?)
+                    ORDER BY l.source_id
+                    LIMIT ?
+                ) broken ON broken.source_id = l.source_id
+                LEFT JOIN uuid target ON target.id = l.target_id
+                WHERE target.id IS NULL OR target.trashed = 1
+                ORDER BY l.source_id, l.path
+            "#,
+            payload.after,
+            payload.after,
+            payload.first + 1,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let mut source_ids: Vec<i64> = records.iter().map(|record| record.source_id).collect();
+        source_ids.dedup();
+        let has_next_page = source_ids.len() as i32 > payload.first;
+        let last_source_id = source_ids.get(payload.first as usize).copied();
+
+        let broken_links = records
+            .into_iter()
+            .filter(|record| Some(record.source_id) != last_source_id)
+            .map(|record| broken_links_query::BrokenLink {
+                source_id: record.source_id as i32,
+                target_id: record.target_id as i32,
+                path: record.path,
+                reason: if record.missing { "missing" } else { "trashed" }.to_string(),
+            })
+            .collect();
+
+        Ok(broken_links_query::Output {
+            broken_links,
+            has_next_page,
+        })
+    }
+}
+
+fn to_typename(entity_type: Option<String>) -> String {
+    match entity_type {
+        Some(entity_type) => entity_type
+            .parse::<EntityType>()
+            .ok()
+            .and_then(|entity_type| serde_json::to_value(entity_type).ok())
+            .and_then(|typename| typename.as_str().map(String::from))
+            .unwrap_or(entity_type),
+        None => "Page".to_string(),
+    }
+}
+
+/// Returns the paths of all internal links in `content`. For editor states the `href` attributes
+/// are used, legacy markdown content is scanned for `[text](url)` links.
+pub fn extract_link_paths(content: &str) -> Vec<String> {
+    let mut hrefs = Vec::new();
+
+    match serde_json::from_str::<Value>(content) {
+        Ok(document) if document.is_object() || document.is_array() => {
+            collect_hrefs(&document, &mut hrefs)
+        }
+        _ => {
+            static MARKDOWN_LINK: OnceLock<Regex> = OnceLock::new();
+            let re = MARKDOWN_LINK.get_or_init(|| Regex::new(r"\]\(([^)\s]+)").unwrap());
+            hrefs.extend(
+                re.captures_iter(content)
+                    .map(|captures| captures[1].to_string()),
+            );
+        }
+    }
+
+    let mut paths: Vec<String> = Vec::new();
+    for path in hrefs.iter().filter_map(|href| to_internal_path(href)) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+fn collect_hrefs(value: &Value, hrefs: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match (key.as_str(), value) {
+                    ("href", Value::String(href)) => hrefs.push(href.clone()),
+                    _ => collect_hrefs(value, hrefs),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_hrefs(value, hrefs)),
+        _ => {}
+    }
+}
+
+/// Normalizes links like `https://de.serlo.org/mathe/12345?x=1#a` to paths like `/mathe/12345`.
+/// Links to other hosts return `None`.
+fn to_internal_path(href: &str) -> Option<String> {
+    static SERLO_URL: OnceLock<Regex> = OnceLock::new();
+    let re = SERLO_URL
+        .get_or_init(|| Regex::new(r"^https?://([a-z]+\.)?serlo\.org(?P<path>/.*)?$").unwrap());
+    let href = href.trim();
+
+    let path = match re.captures(href) {
+        Some(captures) => captures.name("path").map_or("/", |path| path.as_str()),
+        None if href.starts_with('/') && !href.starts_with("//") => href,
+        None => return None,
+    };
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let path = path.trim_end_matches('/');
+
+    if path.is_empty() {
+        None
+    } else {
+        Some(path.to_string())
+    }
+}
+
+/// Returns the uuid id of paths like `/12345`, `/12345/title` or `/mathe/12345/title`.
+pub fn uuid_id_from_path(path: &str) -> Option<i32> {
+    static UUID_PATH: OnceLock<Regex> = OnceLock::new();
+    let re = UUID_PATH.get_or_init(|| Regex::new(r"^/(?:[^/]+/)?(?P<id>\d+)(?:/[^/]*)?$").unwrap());
+    re.captures(path)
+        .and_then(|captures| captures.name("id").unwrap().as_str().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_link_paths, uuid_id_from_path};
+
+    #[test]
+    fn extracts_links_from_editor_state() {
+        let content = r#"{"plugin":"rows","state":[{"plugin":"text","state":[
+            {"type":"p","children":[
+                {"type":"a","href":"/12345","children":[{"text":"a"}]},
+                {"type":"a","href":"https://de.serlo.org/mathe/funktionen?x=1#top","children":[]},
+                {"type":"a","href":"https://example.org/12345","children":[]},
+                {"type":"a","href":"/12345/","children":[]}
+            ]}
+        ]}]}"#;
+
+        assert_eq!(
+            extract_link_paths(content),
+            vec!["/12345".to_string(), "/mathe/funktionen".to_string()]
+        );
+    }
+
+    #[test]
+    fn extracts_links_from_legacy_markdown() {
+        assert_eq!(
+            extract_link_paths("See [this](/1855) and [that](http://serlo.org/2/title)."),
+            vec!["/1855".to_string(), "/2/title".to_string()]
+        );
+    }
+
+    #[test]
+    fn parses_uuid_ids_from_paths() {
+        assert_eq!(uuid_id_from_path("/12345"), Some(12345));
+        assert_eq!(uuid_id_from_path("/12345/title"), Some(12345));
+        assert_eq!(uuid_id_from_path("/mathe/12345/title"), Some(12345));
+        assert_eq!(uuid_id_from_path("/mathe/funktionen"), None);
+    }
+}
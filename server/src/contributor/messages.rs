@@ -0,0 +1,93 @@
+use actix_web::HttpResponse;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::model::Contributor;
+use crate::message::MessageResponder;
+use crate::operation::{self, Operation};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ContributorMessage {
+    EntityContributorsQuery(entity_contributors_query::Payload),
+    SubjectContributorsQuery(subject_contributors_query::Payload),
+}
+
+#[async_trait]
+impl MessageResponder for ContributorMessage {
+    async fn handle<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> HttpResponse {
+        match self {
+            ContributorMessage::EntityContributorsQuery(payload) => {
+                payload.handle(acquire_from).await
+            }
+            ContributorMessage::SubjectContributorsQuery(payload) => {
+                payload.handle(acquire_from).await
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Output {
+    pub contributors: Vec<Contributor>,
+}
+
+pub mod entity_contributors_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub id: i32,
+        pub since: Option<String>,
+        pub until: Option<String>,
+        pub first: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            let contributors = Contributor::fetch_for_entity(self, acquire_from).await?;
+            Ok(Output { contributors })
+        }
+    }
+}
+
+pub mod subject_contributors_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub subject_id: i32,
+        pub since: Option<String>,
+        pub until: Option<String>,
+        pub first: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            let contributors = Contributor::fetch_for_subject(self, acquire_from).await?;
+            Ok(Output { contributors })
+        }
+    }
+}
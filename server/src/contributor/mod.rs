@@ -0,0 +1,5 @@
+pub use messages::ContributorMessage;
+pub use model::*;
+
+mod messages;
+mod model;
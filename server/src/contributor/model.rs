@@ -0,0 +1,167 @@
+use serde::Serialize;
+
+use super::messages::{entity_contributors_query, subject_contributors_query};
+use crate::datetime::DateTime;
+use crate::operation;
+
+/// Statistics about the revisions an author has written. Accepted and rejected revisions are
+/// counted via the `entity/revision/checkout` and `entity/revision/reject` events. All numbers
+/// only consider revisions created within the requested time window.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contributor {
+    pub user_id: i32,
+    pub username: String,
+    pub edit_count: i32,
+    pub accepted_revision_count: i32,
+    pub rejected_revision_count: i32,
+    pub first_contribution_date: DateTime,
+    pub last_contribution_date: DateTime,
+}
+
+macro_rules! to_contributors {
+    ($records: expr) => {
+        $records
+            .into_iter()
+            .filter_map(|record| match (record.first_date, record.last_date) {
+                (Some(first_date), Some(last_date)) => Some(Contributor {
+                    user_id: record.user_id as i32,
+                    username: record.username,
+                    edit_count: record.edit_count as i32,
+                    accepted_revision_count: record.accepted_count as i32,
+                    rejected_revision_count: record.rejected_count as i32,
+                    first_contribution_date: first_date.into(),
+                    last_contribution_date: last_date.into(),
+                }),
+                _ => None,
+            })
+            .collect()
+    };
+}
+
+impl Contributor {
+    pub async fn fetch_for_entity<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &entity_contributors_query::Payload,
+        acquire_from: A,
+    ) -> Result<Vec<Self>, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let (since, until) = parse_time_window(&payload.since, &payload.until)?;
+
+        let records = sqlx::query!(
+            r#"
+                SELECT
+                    r.author_id AS user_id,
+                    user.username,
+                    COUNT(r.id) AS edit_count,
+                    COUNT(CASE WHEN EXISTS (
+                        SELECT 1
+                        FROM event_log el
+                        JOIN event ON event.id = el.event_id
+                        WHERE el.uuid_id = r.id AND event.name = "entity/revision/checkout"
+                    ) THEN 1 END) AS accepted_count,
+                    COUNT(CASE WHEN EXISTS (
+                        SELECT 1
+                        FROM event_log el
+                        JOIN event ON event.id = el.event_id
+                        WHERE el.uuid_id = r.id AND event.name = "entity/revision/reject"
+                    ) THEN 1 END) AS rejected_count,
+                    MIN(r.date) AS first_date,
+                    MAX(r.date) AS last_date
+                FROM entity_revision r
+                JOIN user ON user.id = r.author_id
+                WHERE r.repository_id = ?
+                    AND (? IS NULL OR r.date >= ?)
+                    AND (? IS NULL OR r.date < ?)
+                GROUP BY r.author_id, user.username
+                ORDER BY edit_count DESC, user_id
+                LIMIT ?
+            "#,
+            payload.id,
+            since,
+            since,
+            until,
+            until,
+            payload.first,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        Ok(to_contributors!(records))
+    }
+
+    pub async fn fetch_for_subject<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &subject_contributors_query::Payload,
+        acquire_from: A,
+    ) -> Result<Vec<Self>, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let (since, until) = parse_time_window(&payload.since, &payload.until)?;
+
+        let records = sqlx::query!(
+            r#"
+                WITH subject_entities AS (
+                    SELECT tte.entity_id
+                    FROM term_taxonomy_entity tte
+                    JOIN subject_mapping ON subject_mapping.term_taxonomy_id = tte.term_taxonomy_id
+                    WHERE subject_mapping.subject_id = ? AND subject_mapping.excluded = 0
+
+                    UNION
+
+                    SELECT el.child_id
+                    FROM term_taxonomy_entity tte
+                    JOIN subject_mapping ON subject_mapping.term_taxonomy_id = tte.term_taxonomy_id
+                    JOIN entity_link el ON el.parent_id = tte.entity_id
+                    WHERE subject_mapping.subject_id = ? AND subject_mapping.excluded = 0
+                )
+                SELECT
+                    r.author_id AS user_id,
+                    user.username,
+                    COUNT(r.id) AS edit_count,
+                    COUNT(CASE WHEN EXISTS (
+                        SELECT 1
+                        FROM event_log el
+                        JOIN event ON event.id = el.event_id
+                        WHERE el.uuid_id = r.id AND event.name = "entity/revision/checkout"
+                    ) THEN 1 END) AS accepted_count,
+                    COUNT(CASE WHEN EXISTS (
+                        SELECT 1
+                        FROM event_log el
+                        JOIN event ON event.id = el.event_id
+                        WHERE el.uuid_id = r.id AND event.name = "entity/revision/reject"
+                    ) THEN 1 END) AS rejected_count,
+                    MIN(r.date) AS first_date,
+                    MAX(r.date) AS last_date
+                FROM entity_revision r
+                JOIN subject_entities ON subject_entities.entity_id = r.repository_id
+                JOIN uuid u ON u.id = r.repository_id
+                JOIN user ON user.id = r.author_id
+                WHERE u.trashed = 0
+                    AND (? IS NULL OR r.date >= ?)
+                    AND (? IS NULL OR r.date < ?)
+                GROUP BY r.author_id, user.username
+                ORDER BY edit_count DESC, user_id
+                LIMIT ?
+            "#,
+            payload.subject_id,
+            payload.subject_id,
+            since,
+            since,
+            until,
+            until,
+            payload.first,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        Ok(to_contributors!(records))
+    }
+}
+
+fn parse_time_window(
+    since: &Option<String>,
+    until: &Option<String>,
+) -> Result<(Option<DateTime>, Option<DateTime>), operation::Error> {
+    Ok((
+        since.as_ref().map(|date| date.parse()).transpose()?,
+        until.as_ref().map(|date| date.parse()).transpose()?,
+    ))
+}
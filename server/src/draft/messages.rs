@@ -0,0 +1,176 @@
+use actix_web::HttpResponse;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::model::Draft;
+use crate::message::MessageResponder;
+use crate::operation::{self, Operation, SuccessOutput};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum DraftMessage {
+    DraftCreateMutation(draft_create_mutation::Payload),
+    DraftUpdateMutation(draft_update_mutation::Payload),
+    DraftsQuery(drafts_query::Payload),
+    DraftDiscardMutation(draft_discard_mutation::Payload),
+    DraftSubmitMutation(draft_submit_mutation::Payload),
+}
+
+#[async_trait]
+impl MessageResponder for DraftMessage {
+    async fn handle<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> HttpResponse {
+        match self {
+            DraftMessage::DraftCreateMutation(payload) => payload.handle(acquire_from).await,
+            DraftMessage::DraftUpdateMutation(payload) => payload.handle(acquire_from).await,
+            DraftMessage::DraftsQuery(payload) => payload.handle(acquire_from).await,
+            DraftMessage::DraftDiscardMutation(payload) => payload.handle(acquire_from).await,
+            DraftMessage::DraftSubmitMutation(payload) => payload.handle(acquire_from).await,
+        }
+    }
+}
+
+pub mod draft_create_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+        pub repository_id: i32,
+        pub fields: HashMap<String, String>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Draft;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Draft::create(self, acquire_from).await
+        }
+    }
+}
+
+pub mod draft_update_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+        pub repository_id: i32,
+        pub fields: HashMap<String, String>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Draft;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Draft::update(self, acquire_from).await
+        }
+    }
+}
+
+pub mod drafts_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+        pub repository_id: Option<i32>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub drafts: Vec<Draft>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            let mut connection = acquire_from.acquire().await?;
+            let drafts =
+                Draft::fetch_all(self.user_id, self.repository_id, &mut *connection).await?;
+            Ok(Output { drafts })
+        }
+    }
+}
+
+pub mod draft_discard_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+        pub repository_id: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Draft::discard(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
+
+pub mod draft_submit_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+        pub repository_id: i32,
+        pub changes: String,
+        pub needs_review: bool,
+        pub subscribe_this: bool,
+        pub subscribe_this_by_email: bool,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub success: bool,
+        pub revision_id: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            let revision_id = Draft::submit(self, acquire_from).await?;
+            Ok(Output {
+                success: true,
+                revision_id,
+            })
+        }
+    }
+}
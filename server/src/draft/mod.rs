@@ -0,0 +1,5 @@
+pub use messages::DraftMessage;
+pub use model::*;
+
+mod messages;
+mod model;
@@ -0,0 +1,254 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::messages::{
+    draft_create_mutation, draft_discard_mutation, draft_submit_mutation, draft_update_mutation,
+};
+use crate::datetime::DateTime;
+use crate::operation;
+use crate::uuid::abstract_entity_revision::EntityRevisionType;
+use crate::uuid::page::{add_revision_mutation, Page};
+use crate::uuid::{entity_add_revision_mutation, Entity};
+
+/// A private, autosaved draft of a user for an entity or a page. Drafts are stored in the `draft`
+/// table (primary key is (`user_id`, `repository_id`), `fields` holds the fields as JSON object)
+/// and neither create events nor appear in the review queue until they are submitted.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Draft {
+    pub user_id: i32,
+    pub repository_id: i32,
+    pub fields: HashMap<String, String>,
+    pub date_created: DateTime,
+    pub date_updated: DateTime,
+}
+
+impl Draft {
+    pub async fn fetch_all<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        user_id: i32,
+        repository_id: Option<i32>,
+        executor: E,
+    ) -> Result<Vec<Self>, operation::Error> {
+        sqlx::query!(
+            r#"
+                SELECT user_id, repository_id, fields, date_created, date_updated
+                FROM draft
+                WHERE user_id = ?
+                    AND (? IS NULL OR repository_id = ?)
+                ORDER BY date_updated DESC
+            "#,
+            user_id,
+            repository_id,
+            repository_id,
+        )
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .map(|record| {
+            Ok(Draft {
+                user_id: record.user_id as i32,
+                repository_id: record.repository_id as i32,
+                fields: serde_json::from_str(&record.fields)?,
+                date_created: record.date_created.into(),
+                date_updated: record.date_updated.into(),
+            })
+        })
+        .collect()
+    }
+
+    pub async fn fetch<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        user_id: i32,
+        repository_id: i32,
+        executor: E,
+    ) -> Result<Option<Self>, operation::Error> {
+        Ok(Self::fetch_all(user_id, Some(repository_id), executor)
+            .await?
+            .pop())
+    }
+
+    pub async fn create<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &draft_create_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<Self, operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        sqlx::query!(
+            r#"
+                SELECT u.id
+                FROM uuid u
+                LEFT JOIN entity e ON e.id = u.id
+                LEFT JOIN page_repository p ON p.id = u.id
+                WHERE u.id = ?
+                    AND (e.id IS NOT NULL OR p.id IS NOT NULL)
+            "#,
+            payload.repository_id
+        )
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(operation::Error::BadRequest {
+            reason: "repository invalid".to_string(),
+        })?;
+
+        if Self::fetch(payload.user_id, payload.repository_id, &mut *transaction)
+            .await?
+            .is_some()
+        {
+            return Err(operation::Error::BadRequest {
+                reason: "draft already exists".to_string(),
+            });
+        }
+
+        let now = DateTime::now();
+
+        sqlx::query!(
+            r#"
+                INSERT INTO draft (user_id, repository_id, fields, date_created, date_updated)
+                    VALUES (?, ?, ?, ?, ?)
+            "#,
+            payload.user_id,
+            payload.repository_id,
+            serde_json::to_string(&payload.fields)?,
+            now,
+            now,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        let draft = Self::fetch(payload.user_id, payload.repository_id, &mut *transaction)
+            .await?
+            .ok_or(operation::Error::NotFoundError)?;
+
+        transaction.commit().await?;
+
+        Ok(draft)
+    }
+
+    pub async fn update<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &draft_update_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<Self, operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE draft
+                    SET fields = ?, date_updated = ?
+                    WHERE user_id = ? AND repository_id = ?
+            "#,
+            serde_json::to_string(&payload.fields)?,
+            DateTime::now(),
+            payload.user_id,
+            payload.repository_id,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(operation::Error::BadRequest {
+                reason: "draft does not exist".to_string(),
+            });
+        }
+
+        let draft = Self::fetch(payload.user_id, payload.repository_id, &mut *transaction)
+            .await?
+            .ok_or(operation::Error::NotFoundError)?;
+
+        transaction.commit().await?;
+
+        Ok(draft)
+    }
+
+    pub async fn discard<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &draft_discard_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+
+        let result = sqlx::query!(
+            r#"DELETE FROM draft WHERE user_id = ? AND repository_id = ?"#,
+            payload.user_id,
+            payload.repository_id,
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(operation::Error::BadRequest {
+                reason: "draft does not exist".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Turns the draft into a regular revision via [`Entity::add_revision`] or
+    /// [`Page::add_revision`] and deletes it afterwards. Returns the id of the new revision.
+    pub async fn submit<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &draft_submit_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<i32, operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        let draft = Self::fetch(payload.user_id, payload.repository_id, &mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: "draft does not exist".to_string(),
+            })?;
+
+        let revision =
+            match Entity::fetch_entity_type(draft.repository_id, &mut *transaction).await? {
+                Some(entity_type) => {
+                    Entity::add_revision(
+                        &entity_add_revision_mutation::Payload {
+                            input: entity_add_revision_mutation::Input {
+                                changes: payload.changes.clone(),
+                                entity_id: draft.repository_id,
+                                needs_review: payload.needs_review,
+                                subscribe_this: payload.subscribe_this,
+                                subscribe_this_by_email: payload.subscribe_this_by_email,
+                                fields: draft.fields,
+                            },
+                            revision_type: EntityRevisionType::from(entity_type),
+                            user_id: payload.user_id,
+                        },
+                        &mut *transaction,
+                    )
+                    .await?
+                }
+                None => {
+                    let field = |name: &str| {
+                        draft
+                            .fields
+                            .get(name)
+                            .cloned()
+                            .ok_or(operation::Error::BadRequest {
+                                reason: format!("draft has no field `{name}`"),
+                            })
+                    };
+
+                    Page::add_revision(
+                        &add_revision_mutation::Payload {
+                            content: field("content")?,
+                            title: field("title")?,
+                            page_id: draft.repository_id,
+                            user_id: payload.user_id,
+                        },
+                        &mut *transaction,
+                    )
+                    .await?
+                }
+            };
+
+        sqlx::query!(
+            r#"DELETE FROM draft WHERE user_id = ? AND repository_id = ?"#,
+            payload.user_id,
+            payload.repository_id,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(revision.id)
+    }
+}
@@ -12,8 +12,10 @@ use super::create_entity_revision::CreateEntityRevisionEvent;
 use super::create_thread::CreateThreadEvent;
 use super::entity_link::EntityLinkEvent;
 use super::event_type::{EventType, RawEventType};
+use super::move_thread::MoveThreadEvent;
 use super::revision::RevisionEvent;
 use super::set_license::SetLicenseEvent;
+use super::set_page_metadata::SetPageMetadataEvent;
 use super::set_taxonomy_parent::SetTaxonomyParentEvent;
 use super::set_thread_state::SetThreadStateEvent;
 use super::set_uuid_state::SetUuidStateEvent;
@@ -40,8 +42,10 @@ pub enum ConcreteEvent {
     SetThreadState(SetThreadStateEvent),
     CreateComment(CreateCommentEvent),
     CreateThread(CreateThreadEvent),
+    MoveThread(MoveThreadEvent),
     CreateEntity(CreateEntityEvent),
     SetLicense(SetLicenseEvent),
+    SetPageMetadata(SetPageMetadataEvent),
     CreateEntityLink(EntityLinkEvent),
     RemoveEntityLink(EntityLinkEvent),
     CreateEntityRevision(CreateEntityRevisionEvent),
@@ -91,6 +95,7 @@ impl TryFrom<AbstractEvent> for Event {
                 ConcreteEvent::CreateTaxonomyTerm(abstract_event_ref.into())
             }
             EventType::CreateThread => ConcreteEvent::CreateThread(abstract_event_ref.try_into()?),
+            EventType::MoveThread => ConcreteEvent::MoveThread(abstract_event_ref.try_into()?),
             EventType::RejectRevision => {
                 ConcreteEvent::RejectRevision(abstract_event_ref.try_into()?)
             }
@@ -101,6 +106,7 @@ impl TryFrom<AbstractEvent> for Event {
                 ConcreteEvent::RemoveTaxonomyLink(abstract_event_ref.try_into()?)
             }
             EventType::SetLicense => ConcreteEvent::SetLicense(abstract_event_ref.into()),
+            EventType::SetPageMetadata => ConcreteEvent::SetPageMetadata(abstract_event_ref.into()),
             EventType::SetTaxonomyParent => {
                 ConcreteEvent::SetTaxonomyParent(abstract_event_ref.try_into()?)
             }
@@ -258,6 +264,61 @@ impl EventPayload {
     }
 }
 
+/// Converts a record with the columns selected in [`Event::fetch_events`] to an abstract event.
+/// Records with an unknown instance or event type are skipped by returning `None`.
+macro_rules! to_abstract_event {
+    ($record: expr) => {{
+        let record = $record;
+        match (
+            record.instance.parse::<Instance>(),
+            record.raw_typename.parse::<RawEventType>(),
+        ) {
+            (Ok(instance), Ok(raw_typename)) => {
+                let string_parameters = record
+                    .string_parameters
+                    .and_then(|value| {
+                        value.as_object().map(|object| {
+                            object
+                                .iter()
+                                .filter_map(|(key, value)| {
+                                    value.as_str().map(|value| (key.clone(), value.to_string()))
+                                })
+                                .collect()
+                        })
+                    })
+                    .unwrap_or_default();
+
+                let uuid_parameters = record
+                    .uuid_parameters
+                    .and_then(|value| {
+                        value.as_object().map(|object| {
+                            object
+                                .iter()
+                                .filter_map(|(key, value)| {
+                                    value.as_i64().map(|value| (key.clone(), value as i32))
+                                })
+                                .collect()
+                        })
+                    })
+                    .unwrap_or_default();
+
+                Some(AbstractEvent {
+                    __typename: raw_typename.clone().into(),
+                    id: record.id as i32,
+                    instance,
+                    actor_id: record.actor_id as i32,
+                    object_id: record.object_id as i32,
+                    date: record.date.into(),
+                    raw_typename,
+                    string_parameters: EventStringParameters(string_parameters),
+                    uuid_parameters: EventUuidParameters(uuid_parameters),
+                })
+            }
+            _ => None,
+        }
+    }};
+}
+
 impl Event {
     pub async fn fetch_events<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
         payload: &events_query::Payload,
@@ -330,54 +391,9 @@ impl Event {
                 has_next_page = true;
                 break;
             }
-            let instance = match record.instance.parse() {
-                Ok(instance) => instance,
-                _ => continue,
-            };
-
-            let raw_typename: RawEventType = match record.raw_typename.parse() {
-                Ok(typename) => typename,
-                _ => continue,
-            };
-
-            let string_parameters = record
-                .string_parameters
-                .and_then(|value| {
-                    value.as_object().map(|object| {
-                        object
-                            .iter()
-                            .filter_map(|(key, value)| {
-                                value.as_str().map(|value| (key.clone(), value.to_string()))
-                            })
-                            .collect()
-                    })
-                })
-                .unwrap_or_default();
-
-            let uuid_parameters = record
-                .uuid_parameters
-                .and_then(|value| {
-                    value.as_object().map(|object| {
-                        object
-                            .iter()
-                            .filter_map(|(key, value)| {
-                                value.as_i64().map(|value| (key.clone(), value as i32))
-                            })
-                            .collect()
-                    })
-                })
-                .unwrap_or_default();
-
-            let abstract_event = AbstractEvent {
-                __typename: raw_typename.clone().into(),
-                id: record.id as i32,
-                instance,
-                actor_id: record.actor_id as i32,
-                object_id: record.object_id as i32,
-                date: record.date.into(),
-                raw_typename,
-                string_parameters: EventStringParameters(string_parameters),
-                uuid_parameters: EventUuidParameters(uuid_parameters),
+            let abstract_event = match to_abstract_event!(record) {
+                Some(abstract_event) => abstract_event,
+                None => continue,
             };
 
             if let Ok(event) = abstract_event.try_into() {
@@ -390,4 +406,65 @@ impl Event {
             has_next_page,
         })
     }
+
+    /// Fetches the events with the given ids in one query. The result holds one entry per given
+    /// id in the same order, so repeated ids yield separate events. Like in
+    /// [`Event::fetch_events`], events with an unknown instance or type are `None`.
+    pub async fn fetch_all<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        ids: &[i32],
+        acquire_from: A,
+    ) -> Result<Vec<Option<Event>>, sqlx::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let ids_json = serde_json::to_string(ids).expect("ids are serializable");
+
+        let records = sqlx::query!(
+            r#"
+                SELECT
+                    ids.position,
+                    el.id,
+                    i.subdomain                           AS instance,
+                    e.name                                AS raw_typename,
+                    el.actor_id,
+                    el.date,
+                    el.uuid_id                            AS object_id,
+                    JSON_REMOVE(
+                        JSON_OBJECTAGG(
+                            CASE WHEN epn.name IS NOT NULL THEN epn.name ELSE "__unused_key" END,
+                            eps.value
+                        ),
+                        "$.__unused_key"
+                    )   AS string_parameters,
+                    JSON_REMOVE(
+                        JSON_OBJECTAGG(
+                            CASE WHEN epn.name IS NOT NULL THEN epn.name ELSE "__unused_key" END,
+                            epu.uuid_id
+                        ),
+                        "$.__unused_key"
+                    ) AS uuid_parameters
+                FROM JSON_TABLE(?, "$[*]" COLUMNS (position FOR ORDINALITY, id INT PATH "$")) ids
+                    JOIN event_log el ON el.id = ids.id
+                    JOIN event e ON e.id = el.event_id
+                    JOIN instance i on i.id = el.instance_id
+                    LEFT JOIN event_parameter ep ON ep.log_id = el.id
+                    LEFT JOIN event_parameter_name epn ON epn.id = ep.name_id
+                    LEFT JOIN event_parameter_string eps ON eps.event_parameter_id = ep.id
+                    LEFT JOIN event_parameter_uuid epu ON epu.event_parameter_id = ep.id
+                GROUP BY ids.position, el.id
+            "#,
+            ids_json
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let mut events: Vec<Option<Event>> = ids.iter().map(|_| None).collect();
+
+        for record in records {
+            let position = record.position as usize - 1;
+            if let Some(abstract_event) = to_abstract_event!(record) {
+                events[position] = Event::try_from(abstract_event).ok();
+            }
+        }
+
+        Ok(events)
+    }
 }
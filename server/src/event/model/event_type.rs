@@ -14,10 +14,14 @@ pub enum RawEventType {
     CreateComment,
     #[serde(rename = "discussion/create")]
     CreateThread,
+    #[serde(rename = "discussion/move")]
+    MoveThread,
     #[serde(rename = "entity/create")]
     CreateEntity,
     #[serde(rename = "license/object/set")]
     SetLicense,
+    #[serde(rename = "page/metadata/set")]
+    SetPageMetadata,
     #[serde(rename = "entity/link/create")]
     CreateEntityLink,
     #[serde(rename = "entity/link/remove")]
@@ -74,10 +78,14 @@ pub enum EventType {
     CreateComment,
     #[serde(rename = "CreateThreadNotificationEvent")]
     CreateThread,
+    #[serde(rename = "MoveThreadNotificationEvent")]
+    MoveThread,
     #[serde(rename = "CreateEntityNotificationEvent")]
     CreateEntity,
     #[serde(rename = "SetLicenseNotificationEvent")]
     SetLicense,
+    #[serde(rename = "SetPageMetadataNotificationEvent")]
+    SetPageMetadata,
     #[serde(rename = "CreateEntityLinkNotificationEvent")]
     CreateEntityLink,
     #[serde(rename = "RemoveEntityLinkNotificationEvent")]
@@ -102,6 +110,56 @@ pub enum EventType {
     SetUuidState,
 }
 
+impl std::str::FromStr for EventType {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::value::Value::String(s.to_string()))
+    }
+}
+
+impl sqlx::Type<MySql> for EventType {
+    fn type_info() -> MySqlTypeInfo {
+        <str as sqlx::Type<MySql>>::type_info()
+    }
+}
+impl<'q> sqlx::Encode<'q, MySql> for EventType {
+    fn encode_by_ref(&self, buf: &mut <MySql as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        <&str as sqlx::Encode<'_, MySql>>::encode_by_ref(
+            &serde_json::to_value(self).unwrap().as_str().unwrap(),
+            buf,
+        )
+    }
+}
+
+impl EventType {
+    /// The raw event names which are mapped to this event type.
+    pub fn raw_event_types(&self) -> Vec<RawEventType> {
+        match self {
+            EventType::SetThreadState => {
+                vec![RawEventType::ArchiveThread, RawEventType::RestoreThread]
+            }
+            EventType::CreateComment => vec![RawEventType::CreateComment],
+            EventType::CreateThread => vec![RawEventType::CreateThread],
+            EventType::MoveThread => vec![RawEventType::MoveThread],
+            EventType::CreateEntity => vec![RawEventType::CreateEntity],
+            EventType::SetLicense => vec![RawEventType::SetLicense],
+            EventType::SetPageMetadata => vec![RawEventType::SetPageMetadata],
+            EventType::CreateEntityLink => vec![RawEventType::CreateEntityLink],
+            EventType::RemoveEntityLink => vec![RawEventType::RemoveEntityLink],
+            EventType::CreateEntityRevision => vec![RawEventType::CreateEntityRevision],
+            EventType::CheckoutRevision => vec![RawEventType::CheckoutRevision],
+            EventType::RejectRevision => vec![RawEventType::RejectRevision],
+            EventType::CreateTaxonomyLink => vec![RawEventType::CreateTaxonomyLink],
+            EventType::RemoveTaxonomyLink => vec![RawEventType::RemoveTaxonomyLink],
+            EventType::CreateTaxonomyTerm => vec![RawEventType::CreateTaxonomyTerm],
+            EventType::SetTaxonomyTerm => vec![RawEventType::SetTaxonomyTerm],
+            EventType::SetTaxonomyParent => vec![RawEventType::SetTaxonomyParent],
+            EventType::SetUuidState => vec![RawEventType::RestoreUuid, RawEventType::TrashUuid],
+        }
+    }
+}
+
 impl From<RawEventType> for EventType {
     fn from(raw_event_type: RawEventType) -> Self {
         match raw_event_type {
@@ -109,8 +167,10 @@ impl From<RawEventType> for EventType {
             RawEventType::RestoreThread => EventType::SetThreadState,
             RawEventType::CreateComment => EventType::CreateComment,
             RawEventType::CreateThread => EventType::CreateThread,
+            RawEventType::MoveThread => EventType::MoveThread,
             RawEventType::CreateEntity => EventType::CreateEntity,
             RawEventType::SetLicense => EventType::SetLicense,
+            RawEventType::SetPageMetadata => EventType::SetPageMetadata,
             RawEventType::CreateEntityLink => EventType::CreateEntityLink,
             RawEventType::RemoveEntityLink => EventType::RemoveEntityLink,
             RawEventType::CreateEntityRevision => EventType::CreateEntityRevision,
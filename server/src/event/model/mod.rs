@@ -10,9 +10,11 @@ pub use self::create_thread::*;
 pub use self::entity_link::*;
 pub use self::event::*;
 pub use self::event_type::*;
+pub use self::move_thread::*;
 pub use self::remove_taxonomy_link::*;
 pub use self::revision::*;
 pub use self::set_license::*;
+pub use self::set_page_metadata::*;
 pub use self::set_taxonomy_parent::*;
 pub use self::set_taxonomy_term::*;
 pub use self::set_thread_state::*;
@@ -30,9 +32,11 @@ mod create_thread;
 mod entity_link;
 mod event;
 mod event_type;
+mod move_thread;
 mod remove_taxonomy_link;
 mod revision;
 mod set_license;
+mod set_page_metadata;
 mod set_taxonomy_parent;
 mod set_taxonomy_term;
 mod set_thread_state;
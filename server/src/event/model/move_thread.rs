@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Serialize;
+
+use super::abstract_event::AbstractEvent;
+use super::event::{Event, EventPayload};
+use super::event_type::RawEventType;
+use super::EventError;
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveThreadEvent {
+    thread_id: i32,
+    previous_object_id: i32,
+    object_id: i32,
+}
+
+impl TryFrom<&AbstractEvent> for MoveThreadEvent {
+    type Error = EventError;
+
+    fn try_from(abstract_event: &AbstractEvent) -> Result<Self, Self::Error> {
+        let thread_id = abstract_event.object_id;
+        let previous_object_id = abstract_event.uuid_parameters.try_get("from")?;
+        let object_id = abstract_event.uuid_parameters.try_get("to")?;
+
+        Ok(Self {
+            thread_id,
+            previous_object_id,
+            object_id,
+        })
+    }
+}
+
+pub struct MoveThreadEventPayload {
+    raw_typename: RawEventType,
+    actor_id: i32,
+    thread_id: i32,
+    previous_object_id: i32,
+    object_id: i32,
+    instance_id: i32,
+}
+
+impl MoveThreadEventPayload {
+    pub fn new(
+        thread_id: i32,
+        previous_object_id: i32,
+        object_id: i32,
+        actor_id: i32,
+        instance_id: i32,
+    ) -> Self {
+        Self {
+            raw_typename: RawEventType::MoveThread,
+            actor_id,
+            thread_id,
+            previous_object_id,
+            object_id,
+            instance_id,
+        }
+    }
+
+    pub async fn save<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> Result<Event, EventError> {
+        EventPayload::new(
+            self.raw_typename.clone(),
+            self.actor_id,
+            self.thread_id,
+            self.instance_id,
+            HashMap::new(),
+            [
+                ("from".to_string(), self.previous_object_id),
+                ("to".to_string(), self.object_id),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .save(acquire_from)
+        .await
+    }
+}
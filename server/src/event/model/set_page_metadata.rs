@@ -0,0 +1,109 @@
+use crate::event::{Event, EventError, EventPayload};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::abstract_event::AbstractEvent;
+use super::RawEventType;
+
+/// The instance of the page after the change is the instance of the event.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetPageMetadataEvent {
+    page_id: i32,
+    previous_instance: String,
+    previous_discussions_enabled: bool,
+    discussions_enabled: bool,
+    previous_forum_id: Option<i32>,
+    forum_id: Option<i32>,
+}
+
+impl From<&AbstractEvent> for SetPageMetadataEvent {
+    fn from(abstract_event: &AbstractEvent) -> Self {
+        let page_id = abstract_event.object_id;
+        let string_parameters = &abstract_event.string_parameters;
+        let uuid_parameters = &abstract_event.uuid_parameters;
+
+        SetPageMetadataEvent {
+            page_id,
+            previous_instance: string_parameters.get_or("previous_instance", ""),
+            previous_discussions_enabled: string_parameters
+                .get_or("previous_discussions_enabled", "false")
+                == "true",
+            discussions_enabled: string_parameters.get_or("discussions_enabled", "false") == "true",
+            previous_forum_id: uuid_parameters.get("previous_forum"),
+            forum_id: uuid_parameters.get("forum"),
+        }
+    }
+}
+
+/// Discussion settings of a page before or after a change
+pub struct PageDiscussions {
+    pub enabled: bool,
+    pub forum_id: Option<i32>,
+}
+
+pub struct SetPageMetadataEventPayload {
+    raw_typename: RawEventType,
+    actor_id: i32,
+    instance_id: i32,
+    page_id: i32,
+    previous_instance: String,
+    previous_discussions: PageDiscussions,
+    discussions: PageDiscussions,
+}
+
+impl SetPageMetadataEventPayload {
+    pub fn new(
+        page_id: i32,
+        actor_id: i32,
+        instance_id: i32,
+        previous_instance: String,
+        previous_discussions: PageDiscussions,
+        discussions: PageDiscussions,
+    ) -> Self {
+        Self {
+            raw_typename: RawEventType::SetPageMetadata,
+            actor_id,
+            instance_id,
+            page_id,
+            previous_instance,
+            previous_discussions,
+            discussions,
+        }
+    }
+
+    pub async fn save<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> Result<Event, EventError> {
+        let string_parameters: HashMap<String, String> = [
+            ("previous_instance", self.previous_instance.clone()),
+            (
+                "previous_discussions_enabled",
+                self.previous_discussions.enabled.to_string(),
+            ),
+            ("discussions_enabled", self.discussions.enabled.to_string()),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+        let uuid_parameters: HashMap<String, i32> = [
+            ("previous_forum", self.previous_discussions.forum_id),
+            ("forum", self.discussions.forum_id),
+        ]
+        .into_iter()
+        .filter_map(|(name, forum_id)| forum_id.map(|id| (name.to_string(), id)))
+        .collect();
+
+        EventPayload::new(
+            self.raw_typename.clone(),
+            self.actor_id,
+            self.page_id,
+            self.instance_id,
+            string_parameters,
+            uuid_parameters,
+        )
+        .save(acquire_from)
+        .await
+    }
+}
@@ -11,14 +11,20 @@ use sqlx::{MySql, MySqlPool};
 use thiserror::Error;
 
 pub mod alias;
+pub mod backlink;
+pub mod contributor;
 pub mod datetime;
+pub mod draft;
 pub mod event;
 pub mod instance;
 pub mod message;
 pub mod metadata;
+pub mod moderation;
 pub mod notification;
 pub mod operation;
+pub mod review;
 pub mod routes;
+pub mod search;
 pub mod subject;
 pub mod subscription;
 pub mod thread;
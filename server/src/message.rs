@@ -4,9 +4,15 @@ use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
 
 use crate::alias::AliasMessage;
+use crate::backlink::BacklinkMessage;
+use crate::contributor::ContributorMessage;
+use crate::draft::DraftMessage;
 use crate::event::EventMessage;
 use crate::metadata::MetadataMessage;
+use crate::moderation::ModerationMessage;
 use crate::notification::NotificationMessage;
+use crate::review::ReviewMessage;
+use crate::search::SearchMessage;
 use crate::subject::SubjectsMessage;
 use crate::subscription::SubscriptionMessage;
 use crate::thread::ThreadMessage;
@@ -28,11 +34,17 @@ pub trait MessageResponder {
 #[enum_dispatch(MessageResponder)]
 pub enum Message {
     AliasMessage(AliasMessage),
+    BacklinkMessage(BacklinkMessage),
+    ContributorMessage(ContributorMessage),
+    DraftMessage(DraftMessage),
     EntityMessage(EntityMessage),
     EventMessage(EventMessage),
     MetadataMessage(MetadataMessage),
+    ModerationMessage(ModerationMessage),
     NotificationMessage(NotificationMessage),
     PageMessage(PageMessage),
+    ReviewMessage(ReviewMessage),
+    SearchMessage(SearchMessage),
     SubjectsMessage(SubjectsMessage),
     SubscriptionMessage(SubscriptionMessage),
     TaxonomyTermMessage(TaxonomyTermMessage),
@@ -8,6 +8,7 @@ use std::env;
 use crate::message::MessageResponder;
 use crate::operation::Error;
 use crate::operation::{self, Operation};
+use crate::subject::{amb_scheme, SubjectRegistryEntry, SubjectRegistryKind};
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type", content = "payload")]
@@ -145,32 +146,15 @@ pub mod entities_metadata_query {
         };
 
         let mut connection = acquire_from.acquire().await?;
+        let registry: HashMap<i32, SubjectRegistryEntry> =
+            SubjectRegistryEntry::fetch_all(&Some(SubjectRegistryKind::Subject), &mut *connection)
+                .await?
+                .into_iter()
+                .map(|entry| (entry.taxonomy_term_id, entry))
+                .collect();
+
         Ok(sqlx::query!(
             r#"
-                WITH RECURSIVE subject_mapping AS (
-                    SELECT
-                        subject.id AS term_taxonomy_id,
-                        subject.id AS subject_id,
-                        root.id AS root_id
-                    FROM term_taxonomy root
-                    JOIN term_taxonomy subject ON subject.parent_id = root.id
-                    WHERE root.parent_id IS NULL
-                    OR root.id IN (106081, 146728)
-
-                    UNION
-
-                    SELECT
-                        child.id,
-                        subject_mapping.subject_id,
-                        subject_mapping.root_id
-                    FROM term_taxonomy child
-                    JOIN subject_mapping ON subject_mapping.term_taxonomy_id = child.parent_id
-                    -- "Fächer im Aufbau" taxonomy is on the level of normal Serlo subjects, therefore we need a level below it.
-                    -- "Partner" taxonomy is below the subject "Mathematik", but we only want the entities with the specific partner as the subject.
-                    WHERE child.parent_id NOT IN (87993, 106081, 146728)
-                        -- Exclude content under "Baustelle", "Community", "Zum Testen" and "Testbereich" taxonomies
-                        AND child.id NOT IN (75211, 105140, 107772, 135390, 25107, 106082)
-                )
                 SELECT
                     entity.id,
                     JSON_ARRAYAGG(subject_mapping.subject_id) AS subject_ids,
@@ -211,7 +195,11 @@ pub mod entities_metadata_query {
                     AND uuid.trashed = 0
                     AND type.name IN ("applet", "article", "course", "text-exercise",
                                       "text-exercise-group", "video")
-                    AND NOT subject_mapping.subject_id = 146728
+                    -- Exclude content under test areas like "Baustelle", "Community", "Zum Testen" and "Testbereich"
+                    AND subject_mapping.excluded = 0
+                    AND subject_mapping.subject_id NOT IN (
+                        SELECT term_taxonomy_id FROM subject_registry WHERE kind = "partner-root"
+                    )
                 GROUP BY entity.id
                 ORDER BY entity.id
                 LIMIT ?
@@ -343,9 +331,11 @@ pub mod entities_metadata_query {
                             .collect()
                     })
                     .unwrap_or_default();
-                let subject_metadata = Option::from(subject_ids.iter().flat_map(|id| {
-                    map_serlo_subjects_to_amb_standard(*id)
-                }).collect::<Vec<SubjectMetadata>>()).filter(|v| !v.is_empty());
+                let subject_metadata = Option::from(subject_ids.iter()
+                    .filter_map(|id| registry.get(id))
+                    .flat_map(|entry| entry.amb_subjects.iter())
+                    .filter_map(|uri| to_subject_metadata(uri))
+                    .collect::<Vec<SubjectMetadata>>()).filter(|v| !v.is_empty());
                 EntityMetadata {
                     about: subject_metadata,
                     context: json!([
@@ -370,7 +360,7 @@ pub mod entities_metadata_query {
                         "propertyID": "UUID",
                         "value": identifier,
                     }),
-                    image: map_serlo_subjects_to_thumbnail(subject_ids.first()),
+                    image: get_thumbnail(subject_ids.first().and_then(|id| registry.get(id))),
                     in_language: vec![result.instance],
                     is_accessible_for_free: true,
                     is_family_friendly: true,
@@ -437,42 +427,6 @@ pub mod entities_metadata_query {
         })
     }
 
-    enum SchemeId {
-        UniversitySubject,
-        SchoolSubject,
-    }
-
-    impl SchemeId {
-        fn to_scheme_string(&self) -> String {
-            match *self {
-                SchemeId::UniversitySubject => {
-                    "https://w3id.org/kim/hochschulfaechersystematik/scheme".to_string()
-                }
-                SchemeId::SchoolSubject => "http://w3id.org/kim/schulfaecher/".to_string(),
-            }
-        }
-        fn to_id_string(&self) -> String {
-            match *self {
-                SchemeId::UniversitySubject => {
-                    "https://w3id.org/kim/hochschulfaechersystematik/n".to_string()
-                }
-                SchemeId::SchoolSubject => "http://w3id.org/kim/schulfaecher/s".to_string(),
-            }
-        }
-    }
-
-    impl From<RawSubjectMetadata> for SubjectMetadata {
-        fn from(data: RawSubjectMetadata) -> Self {
-            SubjectMetadata {
-                r#type: "Concept".to_string(),
-                id: data.in_scheme.to_id_string() + &data.id,
-                in_scheme: Scheme {
-                    id: data.in_scheme.to_scheme_string(),
-                },
-            }
-        }
-    }
-
     #[derive(Serialize)]
     #[serde(rename_all = "camelCase")]
     struct SubjectMetadata {
@@ -487,225 +441,21 @@ pub mod entities_metadata_query {
         id: String,
     }
 
-    struct RawSubjectMetadata {
-        id: String,
-        in_scheme: SchemeId,
-    }
-
-    fn map_serlo_subjects_to_amb_standard(id: i32) -> Vec<SubjectMetadata> {
-        match id {
-            // Mathematik (Schule)
-            5 | 23593 | 141587 | 169580 => vec![RawSubjectMetadata {
-                id: "1017".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Nachhaltigkeit => Biologie, Ethik (Schule)
-            17744 | 48416 | 242851 => vec![
-                RawSubjectMetadata {
-                    id: "1001".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-                RawSubjectMetadata {
-                    id: "1008".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-            ],
-            // Chemie (Schule)
-            18230 => vec![RawSubjectMetadata {
-                id: "1002".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Biologie (Schule)
-            23362 => vec![RawSubjectMetadata {
-                id: "1001".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Englisch (Schule)
-            25979 | 107557 | 113127 => vec![RawSubjectMetadata {
-                id: "1007".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Latein (Schule)
-            33894 | 106085 => vec![RawSubjectMetadata {
-                id: "1016".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Physik (Schule)
-            41107 => vec![RawSubjectMetadata {
-                id: "1022".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Informatik (Schule)
-            47899 => vec![RawSubjectMetadata {
-                id: "1013".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Politik => Politik, Sachunterricht (Schule)
-            79159 | 107556 => vec![
-                RawSubjectMetadata {
-                    id: "1023".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-                RawSubjectMetadata {
-                    id: "1028".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-            ],
-            // Medienbildung => Medienbildung, Informatik (Schule)
-            106083 => vec![
-                RawSubjectMetadata {
-                    id: "1046".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-                RawSubjectMetadata {
-                    id: "1013".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-            ],
-            // Geografie (Schule)
-            106084 => vec![RawSubjectMetadata {
-                id: "1010".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Psychologie (Schule)
-            106086 => vec![RawSubjectMetadata {
-                id: "1043".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Deutsch als Zweitsprache (Schule)
-            112723 => vec![RawSubjectMetadata {
-                id: "1006".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Geschichte (Schule)
-            136362 | 140528 => vec![RawSubjectMetadata {
-                id: "1011".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Wirtschaftskunde (Schule)
-            137757 => vec![RawSubjectMetadata {
-                id: "1033".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Musik (Schule)
-            167849 | 48415 => vec![RawSubjectMetadata {
-                id: "1020".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Spanisch (Schule)
-            190109 => vec![RawSubjectMetadata {
-                id: "1030".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Italienisch (Schule)
-            198076 => vec![RawSubjectMetadata {
-                id: "1014".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Religionen, deren Wahrnehmung und Vorurteile => Ethik, Geschichte (Schule)
-            208736 => vec![
-                RawSubjectMetadata {
-                    id: "1008".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-                RawSubjectMetadata {
-                    id: "1011".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-            ],
-            // Deutsch (Schule)
-            210462 => vec![RawSubjectMetadata {
-                id: "1005".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Französisch (Schule)
-            227992 => vec![RawSubjectMetadata {
-                id: "1009".to_string(),
-                in_scheme: SchemeId::SchoolSubject,
-            }
-            .into()],
-            // Sex Education => Sexualerziehung, Biologie (Schule)
-            78339 => vec![
-                RawSubjectMetadata {
-                    id: "1029".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-                RawSubjectMetadata {
-                    id: "1001".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-            ],
-            // Materialwissenschaft
-            141607 => vec![RawSubjectMetadata {
-                id: "294".to_string(),
-                in_scheme: SchemeId::UniversitySubject,
-            }
-            .into()],
-            // Grammatik => Asiatische Sprachen und Kulturen/Asienwissenschaften
-            140527 => vec![RawSubjectMetadata {
-                id: "187".to_string(),
-                in_scheme: SchemeId::UniversitySubject,
-            }
-            .into()],
-            // Kommunikation => Psychologie, Deutsch (Schule)
-            173235 => vec![
-                RawSubjectMetadata {
-                    id: "1043".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-                RawSubjectMetadata {
-                    id: "1005".to_string(),
-                    in_scheme: SchemeId::SchoolSubject,
-                }
-                .into(),
-            ],
-            _ => vec![],
-        }
+    fn to_subject_metadata(uri: &str) -> Option<SubjectMetadata> {
+        amb_scheme(uri).map(|scheme| SubjectMetadata {
+            r#type: "Concept".to_string(),
+            id: uri.to_string(),
+            in_scheme: Scheme {
+                id: scheme.to_string(),
+            },
+        })
     }
 
-    fn map_serlo_subjects_to_thumbnail(id: Option<&i32>) -> String {
+    fn get_thumbnail(subject: Option<&SubjectRegistryEntry>) -> String {
         let thumbnail_folder: String = "https://de.serlo.org/_assets/img/meta/".into();
-        let thumbnail_image = match id {
-            // Biologie (Schule)
-            Some(23362) => "biologie.png",
-            // Chemie (Schule)
-            Some(18230) => "chemie.png",
-            // Informatik (Schule)
-            Some(47899) => "informatik.png",
-            // Mathematik (Schule)
-            Some(5) => "mathe.png",
-            // Nachhaltigkeit
-            Some(17744) => "nachhaltigkeit.png",
-            // default
-            _ => "serlo.png",
-        };
+        let thumbnail_image = subject
+            .and_then(|subject| subject.thumbnail.as_deref())
+            .unwrap_or("serlo.png");
         thumbnail_folder + thumbnail_image
     }
 }
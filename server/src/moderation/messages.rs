@@ -0,0 +1,203 @@
+use actix_web::HttpResponse;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::model::{FlaggedComment, ModerationAction, ModerationLogEntry, ModerationReason};
+use crate::instance::Instance;
+use crate::message::MessageResponder;
+use crate::operation::{self, Operation, SuccessOutput};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ModerationMessage {
+    CommentFlagMutation(comment_flag_mutation::Payload),
+    CommentMarkAsSpamMutation(comment_mark_as_spam_mutation::Payload),
+    UserTrashCommentsMutation(user_trash_comments_mutation::Payload),
+    ModerationLogQuery(moderation_log_query::Payload),
+    ModerationQueueQuery(moderation_queue_query::Payload),
+}
+
+#[async_trait]
+impl MessageResponder for ModerationMessage {
+    async fn handle<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> HttpResponse {
+        match self {
+            ModerationMessage::CommentFlagMutation(payload) => payload.handle(acquire_from).await,
+            ModerationMessage::CommentMarkAsSpamMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+            ModerationMessage::UserTrashCommentsMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+            ModerationMessage::ModerationLogQuery(payload) => payload.handle(acquire_from).await,
+            ModerationMessage::ModerationQueueQuery(payload) => payload.handle(acquire_from).await,
+        }
+    }
+}
+
+pub mod comment_flag_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub comment_id: i32,
+        pub user_id: i32,
+        pub reason: ModerationReason,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            ModerationLogEntry::flag(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
+
+pub mod comment_mark_as_spam_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub comment_id: i32,
+        pub user_id: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            ModerationLogEntry::mark_as_spam(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
+
+pub mod user_trash_comments_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub author_id: i32,
+        pub user_id: i32,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub success: bool,
+        pub trashed_comment_ids: Vec<i32>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Ok(Output {
+                success: true,
+                trashed_comment_ids: ModerationLogEntry::trash_comments_of_user(self, acquire_from)
+                    .await?,
+            })
+        }
+    }
+}
+
+pub mod moderation_log_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub after: Option<i32>,
+        pub comment_id: Option<i32>,
+        pub actor_id: Option<i32>,
+        pub action: Option<ModerationAction>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub entries: Vec<ModerationLogEntry>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            let mut entries = ModerationLogEntry::fetch_all(self, acquire_from).await?;
+            let has_next_page = entries.len() as i32 > self.first;
+            entries.truncate(self.first as usize);
+
+            Ok(Output {
+                entries,
+                has_next_page,
+            })
+        }
+    }
+}
+
+pub mod moderation_queue_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub after: Option<i32>,
+        pub instance: Option<Instance>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub comments: Vec<FlaggedComment>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            let mut comments = FlaggedComment::fetch_queue(self, acquire_from).await?;
+            let has_next_page = comments.len() as i32 > self.first;
+            comments.truncate(self.first as usize);
+
+            Ok(Output {
+                comments,
+                has_next_page,
+            })
+        }
+    }
+}
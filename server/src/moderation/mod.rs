@@ -0,0 +1,5 @@
+pub use messages::ModerationMessage;
+pub use model::*;
+
+mod messages;
+mod model;
@@ -0,0 +1,396 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::messages::{
+    comment_flag_mutation, comment_mark_as_spam_mutation, moderation_log_query,
+    moderation_queue_query, user_trash_comments_mutation,
+};
+use crate::datetime::DateTime;
+use crate::event::SetUuidStateEventPayload;
+use crate::instance::Instance;
+use crate::operation;
+use crate::uuid::{AssertExists, User, Uuid};
+
+/// An entry of the comment moderation log.
+///
+/// The entries are stored in the table `comment_moderation_log`, see
+/// `migrations/20261019080800_create_comment_moderation_log.sql`. A user can take every action
+/// on a comment only once. Entries with action `spam` are the source for training the spam
+/// detection.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationLogEntry {
+    pub id: i32,
+    pub comment_id: i32,
+    pub actor_id: i32,
+    pub action: ModerationAction,
+    pub reason: Option<ModerationReason>,
+    pub date: DateTime,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModerationAction {
+    // A user reported the comment
+    Flag,
+    // A moderator marked the comment as spam, the comment is trashed as well
+    Spam,
+    // The comment was trashed together with all other comments of its author
+    Trash,
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModerationReason {
+    Spam,
+    Abusive,
+    OffTopic,
+    Other,
+}
+
+macro_rules! impl_string_conversions {
+    ($type: ty) => {
+        impl std::str::FromStr for $type {
+            type Err = serde_json::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                serde_json::from_value(serde_json::value::Value::String(s.to_string()))
+            }
+        }
+
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let decoded = serde_json::to_value(self).unwrap();
+                let decoded = decoded.as_str().unwrap();
+                write!(f, "{decoded}")
+            }
+        }
+    };
+}
+
+impl_string_conversions!(ModerationAction);
+impl_string_conversions!(ModerationReason);
+
+/// A comment in the moderation queue. A flagged comment stays in the queue until it is trashed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlaggedComment {
+    pub comment_id: i32,
+    pub flag_count: i32,
+    pub reasons: Vec<ModerationReason>,
+    pub last_flagged_at: DateTime,
+}
+
+impl ModerationLogEntry {
+    pub async fn fetch_all<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &moderation_log_query::Payload,
+        acquire_from: A,
+    ) -> Result<Vec<Self>, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let action = payload.action.as_ref().map(|action| action.to_string());
+
+        sqlx::query!(
+            r#"
+                SELECT id, comment_id, actor_id, action, reason, date
+                FROM comment_moderation_log
+                WHERE (? IS NULL OR id < ?)
+                    AND (? IS NULL OR comment_id = ?)
+                    AND (? IS NULL OR actor_id = ?)
+                    AND (? IS NULL OR action = ?)
+                ORDER BY id DESC
+                LIMIT ?
+            "#,
+            payload.after,
+            payload.after,
+            payload.comment_id,
+            payload.comment_id,
+            payload.actor_id,
+            payload.actor_id,
+            action,
+            action,
+            payload.first + 1,
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|record| {
+            Ok(ModerationLogEntry {
+                id: record.id as i32,
+                comment_id: record.comment_id as i32,
+                actor_id: record.actor_id as i32,
+                action: record.action.parse()?,
+                reason: record.reason.map(|reason| reason.parse()).transpose()?,
+                date: record.date.into(),
+            })
+        })
+        .collect()
+    }
+
+    pub async fn flag<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &comment_flag_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        User::assert_exists(payload.user_id, &mut *transaction).await?;
+
+        let comment = fetch_comment(payload.comment_id, &mut *transaction).await?;
+
+        if comment.trashed {
+            return Err(operation::Error::BadRequest {
+                reason: "trashed comment cannot be flagged".to_string(),
+            });
+        }
+
+        Self::log(
+            payload.comment_id,
+            payload.user_id,
+            &ModerationAction::Flag,
+            Some(&payload.reason),
+            &mut *transaction,
+        )
+        .await
+        .map_err(|error| {
+            if is_duplicate(&error) {
+                operation::Error::BadRequest {
+                    reason: "comment is already flagged by given user".to_string(),
+                }
+            } else {
+                error.into()
+            }
+        })?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_as_spam<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &comment_mark_as_spam_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        User::assert_exists(payload.user_id, &mut *transaction).await?;
+
+        let comment = fetch_comment(payload.comment_id, &mut *transaction).await?;
+
+        trash_comment(&comment, payload.user_id, &mut *transaction).await?;
+
+        // A user marking a comment as spam twice only keeps the first log entry
+        Self::log(
+            payload.comment_id,
+            payload.user_id,
+            &ModerationAction::Spam,
+            None,
+            &mut *transaction,
+        )
+        .await
+        .or_else(ignore_duplicate)?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn trash_comments_of_user<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &user_trash_comments_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<Vec<i32>, operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        User::assert_exists(payload.user_id, &mut *transaction).await?;
+        User::assert_exists(payload.author_id, &mut *transaction).await?;
+
+        let comment_ids: Vec<i32> = sqlx::query!(
+            r#"
+                SELECT comment.id
+                FROM comment
+                JOIN uuid ON uuid.id = comment.id
+                WHERE comment.author_id = ? AND uuid.trashed = 0
+                ORDER BY comment.id
+            "#,
+            payload.author_id
+        )
+        .fetch_all(&mut *transaction)
+        .await?
+        .into_iter()
+        .map(|record| record.id as i32)
+        .collect();
+
+        for comment_id in &comment_ids {
+            let comment = fetch_comment(*comment_id, &mut *transaction).await?;
+
+            trash_comment(&comment, payload.user_id, &mut *transaction).await?;
+
+            Self::log(
+                *comment_id,
+                payload.user_id,
+                &ModerationAction::Trash,
+                None,
+                &mut *transaction,
+            )
+            .await
+            .or_else(ignore_duplicate)?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(comment_ids)
+    }
+
+    async fn log<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        comment_id: i32,
+        actor_id: i32,
+        action: &ModerationAction,
+        reason: Option<&ModerationReason>,
+        executor: E,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+                INSERT INTO comment_moderation_log (comment_id, actor_id, action, reason, date)
+                    VALUES (?, ?, ?, ?, ?)
+            "#,
+            comment_id,
+            actor_id,
+            action.to_string(),
+            reason.map(|reason| reason.to_string()),
+            DateTime::now(),
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
+
+impl FlaggedComment {
+    pub async fn fetch_queue<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &moderation_queue_query::Payload,
+        acquire_from: A,
+    ) -> Result<Vec<Self>, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+
+        // There are only a few reasons, so their concatenation is never truncated
+        sqlx::query!(
+            r#"
+                SELECT
+                    log.comment_id,
+                    COUNT(*) AS flag_count,
+                    GROUP_CONCAT(DISTINCT log.reason) AS reasons,
+                    MAX(log.date) AS "last_flagged_at!"
+                FROM comment_moderation_log log
+                JOIN comment ON comment.id = log.comment_id
+                JOIN uuid ON uuid.id = log.comment_id
+                JOIN instance ON instance.id = comment.instance_id
+                WHERE log.action = "flag"
+                    AND uuid.trashed = 0
+                    AND (? IS NULL OR instance.subdomain = ?)
+                    AND (? IS NULL OR log.comment_id > ?)
+                GROUP BY log.comment_id
+                ORDER BY log.comment_id
+                LIMIT ?
+            "#,
+            payload.instance,
+            payload.instance,
+            payload.after,
+            payload.after,
+            payload.first + 1,
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|record| {
+            let mut reasons = record
+                .reasons
+                .as_deref()
+                .map(|reasons| {
+                    reasons
+                        .split(',')
+                        .map(|reason| reason.parse())
+                        .collect::<Result<Vec<ModerationReason>, _>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
+            reasons.sort();
+
+            Ok(FlaggedComment {
+                comment_id: record.comment_id as i32,
+                flag_count: record.flag_count as i32,
+                reasons,
+                last_flagged_at: record.last_flagged_at.into(),
+            })
+        })
+        .collect()
+    }
+}
+
+fn is_duplicate(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Database(error) if error.is_unique_violation())
+}
+
+fn ignore_duplicate(error: sqlx::Error) -> Result<(), sqlx::Error> {
+    if is_duplicate(&error) {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+struct ModeratedComment {
+    id: i32,
+    trashed: bool,
+    instance: Instance,
+}
+
+async fn fetch_comment<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+    comment_id: i32,
+    executor: E,
+) -> Result<ModeratedComment, operation::Error> {
+    let comment = sqlx::query!(
+        r#"
+            SELECT uuid.trashed, instance.subdomain
+            FROM comment
+            JOIN uuid ON uuid.id = comment.id
+            JOIN instance ON instance.id = comment.instance_id
+            WHERE comment.id = ?
+        "#,
+        comment_id
+    )
+    .fetch_optional(executor)
+    .await?
+    .ok_or(operation::Error::BadRequest {
+        reason: format!("Id {comment_id} does not belong to a comment"),
+    })?;
+
+    Ok(ModeratedComment {
+        id: comment_id,
+        trashed: comment.trashed != 0,
+        instance: comment.subdomain.parse().map_err(|error| {
+            operation::Error::InternalServerError {
+                error: Box::new(error),
+            }
+        })?,
+    })
+}
+
+async fn trash_comment<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+    comment: &ModeratedComment,
+    actor_id: i32,
+    acquire_from: A,
+) -> Result<(), operation::Error> {
+    if comment.trashed {
+        return Ok(());
+    }
+
+    let mut transaction = acquire_from.begin().await?;
+
+    Uuid::set_state(comment.id, true, &mut *transaction).await?;
+
+    SetUuidStateEventPayload::new(true, actor_id, comment.id, comment.instance.clone())
+        .save(&mut *transaction)
+        .await?;
+
+    transaction.commit().await?;
+
+    Ok(())
+}
@@ -1,11 +1,14 @@
 use actix_web::HttpResponse;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
-use crate::event::{AbstractEvent, Event};
+use crate::datetime::DateTime;
+use crate::event::{AbstractEvent, Event, EventType};
+use crate::instance::Instance;
 use crate::subscription::Subscriptions;
+use crate::uuid::{AssertExists, Comment, User};
 
 use crate::message::MessageResponder;
 use crate::operation::{self, Operation, SuccessOutput};
@@ -15,6 +18,12 @@ use crate::operation::{self, Operation, SuccessOutput};
 pub enum NotificationMessage {
     NotificationsQuery(notifications_query::Payload),
     NotificationSetStateMutation(set_state_mutation::Payload),
+    UnreadNotificationCountQuery(unread_count_query::Payload),
+    NotificationSetAllReadMutation(set_all_read_mutation::Payload),
+    PendingEmailNotificationsQuery(pending_email_notifications_query::Payload),
+    MarkNotificationsEmailedMutation(mark_emailed_mutation::Payload),
+    NotificationPreferencesQuery(preferences_query::Payload),
+    NotificationSetPreferenceMutation(set_preference_mutation::Payload),
 }
 
 #[async_trait]
@@ -28,6 +37,24 @@ impl MessageResponder for NotificationMessage {
             NotificationMessage::NotificationSetStateMutation(payload) => {
                 payload.handle(acquire_from).await
             }
+            NotificationMessage::UnreadNotificationCountQuery(payload) => {
+                payload.handle(acquire_from).await
+            }
+            NotificationMessage::NotificationSetAllReadMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+            NotificationMessage::PendingEmailNotificationsQuery(payload) => {
+                payload.handle(acquire_from).await
+            }
+            NotificationMessage::MarkNotificationsEmailedMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+            NotificationMessage::NotificationPreferencesQuery(payload) => {
+                payload.handle(acquire_from).await
+            }
+            NotificationMessage::NotificationSetPreferenceMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
         }
     }
 }
@@ -39,6 +66,10 @@ pub mod notifications_query {
     #[serde(rename_all = "camelCase")]
     pub struct Payload {
         pub user_id: i32,
+        pub first: Option<i32>,
+        pub after: Option<i32>,
+        pub unread_only: Option<bool>,
+        pub event_type: Option<EventType>,
     }
 
     #[async_trait]
@@ -49,7 +80,11 @@ pub mod notifications_query {
             &self,
             acquire_from: A,
         ) -> operation::Result<Self::Output> {
-            Ok(Notifications::fetch(self.user_id, acquire_from).await?)
+            if let Some(first) = self.first {
+                operation::assert_first_is_in_range(first, 10_000)?;
+            }
+
+            Ok(Notifications::fetch(self, acquire_from).await?)
         }
     }
 }
@@ -79,11 +114,360 @@ pub mod set_state_mutation {
     }
 }
 
+pub mod unread_count_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+        pub event_type: Option<EventType>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub user_id: i32,
+        pub unread_count: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Ok(Output {
+                user_id: self.user_id,
+                unread_count: Notifications::count_unread(self, acquire_from).await?,
+            })
+        }
+    }
+}
+
+pub mod set_all_read_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+        pub event_type: Option<EventType>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Notifications::set_all_read(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
+
+pub mod pending_email_notifications_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub window: Option<DigestWindow>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub recipients: Vec<EmailRecipient>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            Ok(Output {
+                recipients: Notifications::fetch_pending_emails(self, acquire_from).await?,
+            })
+        }
+    }
+}
+
+pub mod mark_emailed_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub ids: Vec<i32>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub claimed_ids: Vec<i32>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Ok(Output {
+                claimed_ids: Notifications::mark_emailed(&self.ids, acquire_from).await?,
+            })
+        }
+    }
+}
+
+pub mod preferences_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub user_id: i32,
+        pub preferences: Vec<NotificationPreference>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Ok(Output {
+                user_id: self.user_id,
+                preferences: NotificationPreference::fetch_all(self.user_id, acquire_from).await?,
+            })
+        }
+    }
+}
+
+pub mod set_preference_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+        pub event_type: EventType,
+        // `null` removes the preference, so that the settings of the subscriptions apply again
+        pub delivery: Option<NotificationDelivery>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            NotificationPreference::set(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
+
+/// A digest contains the notifications of the days before the current one (daily) or of the
+/// weeks before the current one (weekly, weeks start on Monday).
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DigestWindow {
+    Daily,
+    Weekly,
+}
+
+/// A user together with the notifications which shall be sent to them via email.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailRecipient {
+    pub user_id: i32,
+    pub email: String,
+    pub instance: Instance,
+    pub notifications: Vec<PendingEmailNotification>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingEmailNotification {
+    pub id: i32,
+    pub event: Event,
+}
+
+/// How a user wants to be notified about events of a certain type.
+///
+/// Preferences are stored in the table `notification_preference`, see
+/// `migrations/20261019081300_create_notification_preference.sql`. `event_type` is the
+/// `__typename` of the event. Without a preference the email setting of the subscription is used.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreference {
+    pub event_type: EventType,
+    pub delivery: NotificationDelivery,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationDelivery {
+    // Notifications are shown in the app and sent via email
+    InAppAndEmail,
+    // Notifications are only shown in the app
+    InApp,
+    // No notifications are created at all
+    Mute,
+}
+
+impl std::str::FromStr for NotificationDelivery {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::value::Value::String(s.to_string()))
+    }
+}
+
+impl sqlx::Type<sqlx::MySql> for NotificationDelivery {
+    fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+        <str as sqlx::Type<sqlx::MySql>>::type_info()
+    }
+}
+impl<'q> sqlx::Encode<'q, sqlx::MySql> for NotificationDelivery {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <sqlx::MySql as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <&str as sqlx::Encode<'_, sqlx::MySql>>::encode_by_ref(
+            &serde_json::to_value(self).unwrap().as_str().unwrap(),
+            buf,
+        )
+    }
+}
+
+impl NotificationPreference {
+    pub async fn fetch_all<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        user_id: i32,
+        acquire_from: A,
+    ) -> Result<Vec<Self>, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+
+        let records = sqlx::query!(
+            r#"
+                SELECT event_type, delivery
+                    FROM notification_preference
+                    WHERE user_id = ?
+                    ORDER BY event_type
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let mut preferences = Vec::with_capacity(records.len());
+        for record in records {
+            preferences.push(NotificationPreference {
+                event_type: record.event_type.parse()?,
+                delivery: record.delivery.parse()?,
+            });
+        }
+
+        Ok(preferences)
+    }
+
+    pub async fn set<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &set_preference_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        User::assert_exists(payload.user_id, &mut *transaction).await?;
+
+        match &payload.delivery {
+            Some(delivery) => {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO notification_preference (user_id, event_type, delivery)
+                            VALUES (?, ?, ?)
+                            ON DUPLICATE KEY UPDATE delivery = ?
+                    "#,
+                    payload.user_id,
+                    payload.event_type,
+                    delivery,
+                    delivery,
+                )
+                .execute(&mut *transaction)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    r#"
+                        DELETE FROM notification_preference
+                            WHERE user_id = ? AND event_type = ?
+                    "#,
+                    payload.user_id,
+                    payload.event_type,
+                )
+                .execute(&mut *transaction)
+                .await?;
+            }
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Returns the deliveries users have chosen for the given event type, keyed by user id.
+    async fn fetch_deliveries<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        event_type: &EventType,
+        acquire_from: A,
+    ) -> Result<HashMap<i32, NotificationDelivery>, sqlx::Error> {
+        let mut connection = acquire_from.acquire().await?;
+
+        Ok(sqlx::query!(
+            r#"
+                SELECT user_id, delivery
+                    FROM notification_preference
+                    WHERE event_type = ?
+            "#,
+            event_type,
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .filter_map(|record| Some((record.user_id as i32, record.delivery.parse().ok()?)))
+        .collect())
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Notifications {
     pub user_id: i32,
     pub notifications: Vec<Notification>,
+    pub has_next_page: bool,
 }
 
 #[derive(Serialize, Debug)]
@@ -115,35 +499,36 @@ impl Hash for Subscriber {
 
 impl Notifications {
     pub async fn fetch<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
-        user_id: i32,
+        payload: &notifications_query::Payload,
         acquire_from: A,
     ) -> Result<Notifications, sqlx::Error> {
         let mut connection = acquire_from.acquire().await?;
+        let raw_event_names = to_raw_event_names(&payload.event_type);
+        let limit = payload.first.map(|first| first + 1);
+
         let notifications = sqlx::query!(
             r#"
-                SELECT n.id, n.seen, n.email_sent, n.email, e.event_log_id
+                SELECT n.id, n.seen, n.email_sent, n.email, MIN(e.event_log_id) AS "event_log_id!"
                     FROM notification n
                     JOIN notification_event e ON n.id = e.notification_id
-                    JOIN event_log on event_log.id = e.event_log_id
-                    JOIN uuid uuid1 on uuid1.id = event_log.uuid_id
-                    LEFT JOIN entity entity1 on entity1.id = event_log.uuid_id
-                    LEFT JOIN event_parameter ON event_parameter.log_id = event_log.id
-                    LEFT JOIN event_parameter_uuid ON
-                      event_parameter_uuid.event_parameter_id = event_parameter.id
-                    LEFT JOIN event_parameter_string ON
-                      event_parameter_string.event_parameter_id = event_parameter.id
-                    LEFT JOIN uuid uuid2 on uuid2.id = event_parameter_uuid.uuid_id
-                    LEFT JOIN entity entity2 on entity2.id = event_parameter_uuid.uuid_id
+                    JOIN visible_event_log on visible_event_log.id = e.event_log_id
+                    JOIN event on event.id = visible_event_log.event_id
                     WHERE n.user_id = ?
-                      AND event_parameter_string.value IS NULL
-                      AND uuid1.discriminator NOT IN ("attachment", "blogPost")
-                      AND (uuid2.discriminator IS NULL OR
-                        uuid2.discriminator NOT IN ("attachment", "blogPost"))
-                      AND (entity1.type_id IS NULL OR entity1.type_id IN (1,2,3,4,5,6,7,8,49,50))
-                      AND (entity2.type_id IS NULL OR entity2.type_id IN (1,2,3,4,5,6,7,8,49,50))
+                      AND (? IS NULL OR ? = FALSE OR n.seen = 0)
+                      AND (? IS NULL OR JSON_CONTAINS(?, JSON_QUOTE(event.name)))
+                      AND (? IS NULL OR (n.date, n.id) < (SELECT date, id FROM notification WHERE id = ?))
+                    GROUP BY n.id, n.seen, n.email_sent, n.email, n.date
                     ORDER BY n.date DESC, n.id DESC
+                    LIMIT ?
             "#,
-            user_id
+            payload.user_id,
+            payload.unread_only,
+            payload.unread_only,
+            raw_event_names,
+            raw_event_names,
+            payload.after,
+            payload.after,
+            limit.unwrap_or(i32::MAX),
         )
         .fetch_all(&mut *connection)
         .await?;
@@ -158,14 +543,49 @@ impl Notifications {
                 event_id: child.event_log_id as i32,
             })
             .collect();
-        notifications.dedup_by(|n1, n2| n1.id == n2.id);
+
+        let has_next_page = payload
+            .first
+            .is_some_and(|first| notifications.len() as i32 > first);
+        if let Some(first) = payload.first {
+            notifications.truncate(first as usize);
+        }
 
         Ok(Notifications {
-            user_id,
+            user_id: payload.user_id,
             notifications,
+            has_next_page,
         })
     }
 
+    /// Counts the unread notifications of a user which [`Notifications::fetch`] would return.
+    pub async fn count_unread<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &unread_count_query::Payload,
+        acquire_from: A,
+    ) -> Result<i32, sqlx::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let raw_event_names = to_raw_event_names(&payload.event_type);
+
+        Ok(sqlx::query!(
+            r#"
+                SELECT COUNT(DISTINCT n.id) AS count
+                    FROM notification n
+                    JOIN notification_event e ON n.id = e.notification_id
+                    JOIN visible_event_log on visible_event_log.id = e.event_log_id
+                    JOIN event on event.id = visible_event_log.event_id
+                    WHERE n.user_id = ?
+                      AND n.seen = 0
+                      AND (? IS NULL OR JSON_CONTAINS(?, JSON_QUOTE(event.name)))
+            "#,
+            payload.user_id,
+            raw_event_names,
+            raw_event_names,
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .count as i32)
+    }
+
     pub async fn create_notifications<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
         event: &Event,
         acquire_from: A,
@@ -198,7 +618,36 @@ impl Notifications {
             }
         }
 
-        for subscriber in subscribers {
+        // Users mentioned in a new comment are notified even when they are not subscribed. They
+        // are only added when they are no subscribers, so that their email setting is kept.
+        if matches!(
+            event.abstract_event.__typename,
+            EventType::CreateComment | EventType::CreateThread
+        ) {
+            for user_id in Comment::fetch_mentioned_user_ids(object_id, &mut *transaction).await? {
+                if user_id != actor_id {
+                    subscribers.insert(Subscriber {
+                        user_id,
+                        send_email: false,
+                    });
+                }
+            }
+        }
+
+        let deliveries = NotificationPreference::fetch_deliveries(
+            &event.abstract_event.__typename,
+            &mut *transaction,
+        )
+        .await?;
+
+        for mut subscriber in subscribers {
+            match deliveries.get(&subscriber.user_id) {
+                Some(NotificationDelivery::Mute) => continue,
+                Some(NotificationDelivery::InApp) => subscriber.send_email = false,
+                Some(NotificationDelivery::InAppAndEmail) => subscriber.send_email = true,
+                None => {}
+            }
+
             Self::create_notification(event, &subscriber, &mut *transaction).await?;
         }
 
@@ -242,6 +691,161 @@ impl Notifications {
 }
 
 impl Notifications {
+    /// Fetches unread notifications which shall be sent via email but have not been sent yet,
+    /// grouped by their recipients. `first` limits the number of recipients, so the
+    /// notifications of a recipient are never split between two results. With a digest window
+    /// only notifications created before the start of the current day or week are returned. The
+    /// instance of a recipient is the instance of their newest pending notification.
+    pub async fn fetch_pending_emails<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &pending_email_notifications_query::Payload,
+        acquire_from: A,
+    ) -> Result<Vec<EmailRecipient>, operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+        let is_weekly = payload
+            .window
+            .as_ref()
+            .map(|window| matches!(window, DigestWindow::Weekly));
+        let now = DateTime::now();
+
+        let pending_notifications = sqlx::query!(
+            r#"
+                WITH pending AS (
+                    SELECT n.id, n.user_id, n.date
+                        FROM notification n
+                        JOIN uuid ON uuid.id = n.user_id
+                        WHERE n.email = 1
+                          AND n.email_sent = 0
+                          AND n.seen = 0
+                          AND uuid.trashed = 0
+                          AND (? IS NULL OR n.date < DATE_SUB(DATE(?), INTERVAL IF(?, WEEKDAY(?), 0) DAY))
+                ),
+                recipients AS (
+                    SELECT DISTINCT user_id
+                        FROM pending
+                        ORDER BY user_id
+                        LIMIT ?
+                )
+                SELECT pending.id, pending.user_id, user.email, MIN(e.event_log_id) AS "event_log_id!"
+                    FROM pending
+                    JOIN recipients ON recipients.user_id = pending.user_id
+                    JOIN notification_event e ON pending.id = e.notification_id
+                    JOIN user ON user.id = pending.user_id
+                    GROUP BY pending.id, pending.user_id, user.email, pending.date
+                    ORDER BY pending.user_id, pending.date, pending.id
+            "#,
+            is_weekly,
+            now,
+            is_weekly,
+            now,
+            payload.first,
+        )
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let event_ids: Vec<i32> = pending_notifications
+            .iter()
+            .map(|notification| notification.event_log_id as i32)
+            .collect();
+        let events = Event::fetch_all(&event_ids, &mut *transaction).await?;
+
+        let mut recipients: Vec<EmailRecipient> = Vec::new();
+
+        for (notification, event) in pending_notifications.into_iter().zip(events) {
+            let user_id = notification.user_id as i32;
+            let event = match event {
+                Some(event) => event,
+                None => continue,
+            };
+            let instance = event.abstract_event.instance.clone();
+            let pending_notification = PendingEmailNotification {
+                id: notification.id,
+                event,
+            };
+
+            // Notifications of a recipient are ordered by date, so the last one is the newest
+            match recipients.last_mut() {
+                Some(recipient) if recipient.user_id == user_id => {
+                    recipient.instance = instance;
+                    recipient.notifications.push(pending_notification)
+                }
+                _ => recipients.push(EmailRecipient {
+                    user_id,
+                    email: notification.email,
+                    instance,
+                    notifications: vec![pending_notification],
+                }),
+            }
+        }
+
+        transaction.commit().await?;
+
+        Ok(recipients)
+    }
+
+    /// Marks the given notifications as sent and returns the ids of those which were still
+    /// pending. Each update only succeeds while `email_sent` is unset, so when two mailers claim
+    /// the same notification only one of them gets its id back and may send the email.
+    pub async fn mark_emailed<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        ids: &[i32],
+        acquire_from: A,
+    ) -> Result<Vec<i32>, sqlx::Error> {
+        let mut transaction = acquire_from.begin().await?;
+        let mut claimed_ids = Vec::new();
+
+        for id in ids {
+            let rows_affected = sqlx::query!(
+                r#"
+                    UPDATE notification
+                        SET email_sent = 1
+                        WHERE id = ? AND email = 1 AND email_sent = 0
+                "#,
+                id
+            )
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+
+            if rows_affected == 1 {
+                claimed_ids.push(*id);
+            }
+        }
+
+        transaction.commit().await?;
+
+        Ok(claimed_ids)
+    }
+
+    pub async fn set_all_read<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &set_all_read_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), sqlx::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let raw_event_names = to_raw_event_names(&payload.event_type);
+
+        sqlx::query!(
+            r#"
+                UPDATE notification n
+                    SET n.seen = 1
+                    WHERE n.user_id = ?
+                      AND n.seen = 0
+                      AND n.id IN (
+                        SELECT e.notification_id
+                            FROM notification_event e
+                            JOIN visible_event_log on visible_event_log.id = e.event_log_id
+                            JOIN event on event.id = visible_event_log.event_id
+                            WHERE (? IS NULL OR JSON_CONTAINS(?, JSON_QUOTE(event.name)))
+                      )
+            "#,
+            payload.user_id,
+            raw_event_names,
+            raw_event_names,
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn set_notification_state<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
         payload: &set_state_mutation::Payload,
         acquire_from: A,
@@ -270,6 +874,15 @@ impl Notifications {
     }
 }
 
+/// Encodes the raw event names of the given event type as JSON array, so that they can be bound
+/// to a single query parameter.
+fn to_raw_event_names(event_type: &Option<EventType>) -> Option<String> {
+    event_type.as_ref().map(|event_type| {
+        serde_json::to_string(&event_type.raw_event_types())
+            .expect("raw event types are serializable")
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,6 +893,16 @@ mod tests {
 
     use test_utils::create_new_test_user;
 
+    fn all_notifications_of(user_id: i32) -> notifications_query::Payload {
+        notifications_query::Payload {
+            user_id,
+            first: None,
+            after: None,
+            unread_only: None,
+            event_type: None,
+        }
+    }
+
     #[actix_rt::test]
     async fn set_notification_state_no_id() {
         let pool = create_database_pool().await.unwrap();
@@ -436,7 +1059,7 @@ mod tests {
 
         // Verify that the notification was created.
         assert_eq!(
-            Notifications::fetch(test_user, &mut *transaction)
+            Notifications::fetch(&all_notifications_of(test_user), &mut *transaction)
                 .await
                 .unwrap()
                 .notifications
@@ -490,9 +1113,10 @@ mod tests {
 
         // Verify that the notifications were created.
         for subscriber in subscribers {
-            let notifications = Notifications::fetch(subscriber, &mut *transaction)
-                .await
-                .unwrap();
+            let notifications =
+                Notifications::fetch(&all_notifications_of(subscriber), &mut *transaction)
+                    .await
+                    .unwrap();
             let notifications: Vec<_> = notifications
                 .notifications
                 .iter()
@@ -14,6 +14,15 @@ pub enum Error {
     InternalServerError { error: Box<dyn std::error::Error> },
     #[error("Requested value could not be found.")]
     NotFoundError,
+    #[error("InvalidFields: {errors:?}")]
+    InvalidFields { errors: Vec<FieldError> },
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
 }
 
 impl From<sqlx::Error> for Error {
@@ -48,6 +57,21 @@ impl From<serde_json::Error> for Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Checks the parameter `first` of paginated queries, which is passed to `LIMIT` in SQL.
+pub fn assert_first_is_in_range(first: i32, maximum: i32) -> Result<()> {
+    if first > maximum {
+        return Err(Error::BadRequest {
+            reason: "parameter `first` is too high".to_string(),
+        });
+    }
+    if first < 0 {
+        return Err(Error::BadRequest {
+            reason: "parameter `first` must not be negative".to_string(),
+        });
+    }
+    Ok(())
+}
+
 #[async_trait]
 pub trait Operation: std::fmt::Debug {
     type Output: Serialize;
@@ -76,6 +100,13 @@ pub trait Operation: std::fmt::Debug {
                     Error::BadRequest { reason } => HttpResponse::BadRequest()
                         .content_type("application/json; charset=utf-8")
                         .json(json!({ "success": false, "reason": reason })),
+                    Error::InvalidFields { errors } => HttpResponse::BadRequest()
+                        .content_type("application/json; charset=utf-8")
+                        .json(json!({
+                            "success": false,
+                            "reason": "invalid fields",
+                            "fieldErrors": errors,
+                        })),
                     Error::InternalServerError { error: _ } => {
                         HttpResponse::InternalServerError().finish()
                     }
@@ -0,0 +1,110 @@
+use actix_web::HttpResponse;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::model::ReviewClaim;
+use crate::message::MessageResponder;
+use crate::operation::{self, Operation, SuccessOutput};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum ReviewMessage {
+    ReviewClaimMutation(review_claim_mutation::Payload),
+    ReviewReleaseClaimMutation(review_release_claim_mutation::Payload),
+    ReviewClaimsQuery(review_claims_query::Payload),
+}
+
+#[async_trait]
+impl MessageResponder for ReviewMessage {
+    async fn handle<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> HttpResponse {
+        match self {
+            ReviewMessage::ReviewClaimMutation(payload) => payload.handle(acquire_from).await,
+            ReviewMessage::ReviewReleaseClaimMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+            ReviewMessage::ReviewClaimsQuery(payload) => payload.handle(acquire_from).await,
+        }
+    }
+}
+
+pub mod review_claim_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub revision_id: i32,
+        pub user_id: i32,
+        pub duration_in_minutes: Option<i32>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = ReviewClaim;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            ReviewClaim::claim(self, acquire_from).await
+        }
+    }
+}
+
+pub mod review_release_claim_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub revision_id: i32,
+        pub user_id: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            ReviewClaim::release(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
+
+pub mod review_claims_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: Option<i32>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub claims: Vec<ReviewClaim>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            let mut connection = acquire_from.acquire().await?;
+            let claims =
+                ReviewClaim::fetch_all_active(None, self.user_id, &mut *connection).await?;
+            Ok(Output { claims })
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub use messages::ReviewMessage;
+pub use model::*;
+
+mod messages;
+mod model;
@@ -0,0 +1,225 @@
+use serde::Serialize;
+
+use super::messages::{review_claim_mutation, review_release_claim_mutation};
+use crate::datetime::DateTime;
+use crate::operation;
+use crate::uuid::{AssertExists, User};
+
+const DEFAULT_CLAIM_DURATION_IN_MINUTES: i32 = 30;
+const MAX_CLAIM_DURATION_IN_MINUTES: i32 = 24 * 60;
+
+/// A reviewer's claim on a pending entity or page revision. Claims are stored in the
+/// `review_claim` table (`revision_id` is the primary key) and are only considered while
+/// `expires_at` lies in the future.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewClaim {
+    pub revision_id: i32,
+    pub repository_id: i32,
+    pub user_id: i32,
+    pub date: DateTime,
+    pub expires_at: DateTime,
+}
+
+impl ReviewClaim {
+    pub async fn fetch_all_active<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        revision_id: Option<i32>,
+        user_id: Option<i32>,
+        executor: E,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        Ok(sqlx::query!(
+            r#"
+                SELECT
+                    c.revision_id,
+                    c.user_id,
+                    c.date,
+                    c.expires_at,
+                    COALESCE(er.repository_id, pr.page_repository_id) AS repository_id
+                FROM review_claim c
+                LEFT JOIN entity_revision er ON er.id = c.revision_id
+                LEFT JOIN page_revision pr ON pr.id = c.revision_id
+                WHERE c.expires_at > ?
+                    AND (? IS NULL OR c.revision_id = ?)
+                    AND (? IS NULL OR c.user_id = ?)
+                ORDER BY c.expires_at
+            "#,
+            DateTime::now(),
+            revision_id,
+            revision_id,
+            user_id,
+            user_id,
+        )
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .filter_map(|record| {
+            record.repository_id.map(|repository_id| ReviewClaim {
+                revision_id: record.revision_id as i32,
+                repository_id: repository_id as i32,
+                user_id: record.user_id as i32,
+                date: record.date.into(),
+                expires_at: record.expires_at.into(),
+            })
+        })
+        .collect())
+    }
+
+    pub async fn fetch_active<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        revision_id: i32,
+        executor: E,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        Ok(Self::fetch_all_active(Some(revision_id), None, executor)
+            .await?
+            .pop())
+    }
+
+    pub async fn claim<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &review_claim_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<Self, operation::Error> {
+        let duration_in_minutes = payload
+            .duration_in_minutes
+            .unwrap_or(DEFAULT_CLAIM_DURATION_IN_MINUTES);
+
+        if duration_in_minutes <= 0 || duration_in_minutes > MAX_CLAIM_DURATION_IN_MINUTES {
+            return Err(operation::Error::BadRequest {
+                reason: "parameter `durationInMinutes` is out of range".to_string(),
+            });
+        }
+
+        let mut transaction = acquire_from.begin().await?;
+
+        User::assert_exists(payload.user_id, &mut *transaction).await?;
+
+        let revision = sqlx::query!(
+            r#"
+                SELECT
+                    u.trashed,
+                    COALESCE(e.current_revision_id, p.current_revision_id) AS current_revision_id
+                FROM uuid u
+                LEFT JOIN entity_revision er ON er.id = u.id
+                LEFT JOIN entity e ON e.id = er.repository_id
+                LEFT JOIN page_revision pr ON pr.id = u.id
+                LEFT JOIN page_repository p ON p.id = pr.page_repository_id
+                WHERE u.id = ?
+                    AND u.discriminator IN ("entityRevision", "pageRevision")
+            "#,
+            payload.revision_id
+        )
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(operation::Error::BadRequest {
+            reason: "revision invalid".to_string(),
+        })?;
+
+        let is_pending = revision.trashed == 0
+            && revision
+                .current_revision_id
+                .map_or(true, |current_revision_id| {
+                    (current_revision_id as i32) < payload.revision_id
+                });
+
+        if !is_pending {
+            return Err(operation::Error::BadRequest {
+                reason: "revision is not pending review".to_string(),
+            });
+        }
+
+        let now = DateTime::now();
+
+        // An existing claim is only overwritten when it has expired or belongs to the same user.
+        // Doing the check within the upsert keeps concurrent claims from overwriting each other.
+        // `user_id` needs to be assigned first since the following assignments see its new value.
+        let rows_affected = sqlx::query!(
+            r#"
+                INSERT INTO review_claim (revision_id, user_id, date, expires_at)
+                    VALUES (?, ?, ?, DATE_ADD(?, INTERVAL ? MINUTE))
+                    ON DUPLICATE KEY UPDATE
+                        user_id = IF(
+                            expires_at <= VALUES(date) OR user_id = VALUES(user_id),
+                            VALUES(user_id),
+                            user_id
+                        ),
+                        date = IF(user_id = VALUES(user_id), VALUES(date), date),
+                        expires_at = IF(user_id = VALUES(user_id), VALUES(expires_at), expires_at)
+            "#,
+            payload.revision_id,
+            payload.user_id,
+            now,
+            now,
+            duration_in_minutes,
+        )
+        .execute(&mut *transaction)
+        .await?
+        .rows_affected();
+
+        let claim = Self::fetch_active(payload.revision_id, &mut *transaction)
+            .await?
+            .ok_or(operation::Error::NotFoundError)?;
+
+        if rows_affected == 0 && claim.user_id != payload.user_id {
+            return Err(operation::Error::BadRequest {
+                reason: format!("revision is already claimed by user {}", claim.user_id),
+            });
+        }
+
+        transaction.commit().await?;
+
+        Ok(claim)
+    }
+
+    pub async fn release<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &review_release_claim_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        if let Some(claim) = Self::fetch_active(payload.revision_id, &mut *transaction).await? {
+            if claim.user_id != payload.user_id {
+                return Err(operation::Error::BadRequest {
+                    reason: format!("revision is claimed by user {}", claim.user_id),
+                });
+            }
+        }
+
+        Self::remove(payload.revision_id, &mut *transaction).await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn remove<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        revision_id: i32,
+        executor: E,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM review_claim WHERE revision_id = ?"#,
+            revision_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    /// Fails when a different reviewer holds an active claim on the revision, unless `force` is
+    /// set. A forced review overrides the claim.
+    pub async fn assert_not_claimed_by_other<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        revision_id: i32,
+        user_id: i32,
+        force: bool,
+        executor: E,
+    ) -> Result<(), operation::Error> {
+        match Self::fetch_active(revision_id, executor).await? {
+            Some(claim) if claim.user_id != user_id && !force => {
+                Err(operation::Error::BadRequest {
+                    reason: format!(
+                        "revision is claimed by user {} until {}, use `force` to review it anyway",
+                        claim.user_id, claim.expires_at
+                    ),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
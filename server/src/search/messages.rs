@@ -0,0 +1,115 @@
+use actix_web::HttpResponse;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::model::SearchIndex;
+use crate::instance::Instance;
+use crate::message::MessageResponder;
+use crate::operation::{self, Operation};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum SearchMessage {
+    SearchQuery(search_query::Payload),
+    SearchIndexRebuildMutation(search_index_rebuild_mutation::Payload),
+}
+
+#[async_trait]
+impl MessageResponder for SearchMessage {
+    async fn handle<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> HttpResponse {
+        match self {
+            SearchMessage::SearchQuery(payload) => payload.handle(acquire_from).await,
+            SearchMessage::SearchIndexRebuildMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+        }
+    }
+}
+
+pub mod search_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub query: String,
+        pub instance: Option<Instance>,
+        pub types: Option<Vec<String>>,
+        pub subject_id: Option<i32>,
+        pub first: i32,
+        pub after: Option<i32>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SearchResult {
+        pub id: i32,
+        #[serde(rename = "__typename")]
+        pub typename: String,
+        pub title: String,
+        pub snippet: String,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub results: Vec<SearchResult>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            if self.query.trim().is_empty() {
+                return Err(operation::Error::BadRequest {
+                    reason: "parameter `query` must not be empty".to_string(),
+                });
+            }
+
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            SearchIndex::search(self, acquire_from).await
+        }
+    }
+}
+
+pub mod search_index_rebuild_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub after: Option<i32>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub success: bool,
+        pub indexed_ids: Vec<i32>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 1_000)?;
+
+            SearchIndex::rebuild(self, acquire_from).await
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub use messages::SearchMessage;
+pub use model::*;
+
+mod messages;
+mod model;
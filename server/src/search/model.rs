@@ -0,0 +1,412 @@
+use serde_json::Value;
+
+use super::messages::{search_index_rebuild_mutation, search_query};
+use crate::operation;
+use crate::uuid::EntityType;
+
+const SNIPPET_LENGTH: usize = 160;
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Full-text search over the current revisions of entities and pages.
+///
+/// The searchable text is stored in the `search_index` table (`uuid_id` is the primary key,
+/// further columns are `instance_id`, `typename`, `title`, `meta_description` and `text`) which
+/// has a FULLTEXT index on (`title`, `meta_description`, `text`). Entries are updated whenever a
+/// revision is checked out; [`SearchIndex::rebuild`] fills the index for existing content.
+pub struct SearchIndex;
+
+struct SearchIndexEntry {
+    instance_id: i32,
+    typename: String,
+    title: String,
+    meta_description: String,
+    text: String,
+}
+
+impl SearchIndex {
+    pub async fn update<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        id: i32,
+        acquire_from: A,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        let entity = sqlx::query!(
+            r#"
+                SELECT
+                    e.instance_id,
+                    type.name AS entity_type,
+                    title.value AS title,
+                    meta_description.value AS meta_description,
+                    content.value AS content
+                FROM entity e
+                JOIN type ON type.id = e.type_id
+                LEFT JOIN entity_revision_field title
+                    ON title.entity_revision_id = e.current_revision_id
+                    AND title.field = "title"
+                LEFT JOIN entity_revision_field meta_description
+                    ON meta_description.entity_revision_id = e.current_revision_id
+                    AND meta_description.field = "meta_description"
+                LEFT JOIN entity_revision_field content
+                    ON content.entity_revision_id = e.current_revision_id
+                    AND content.field = IF(type.name = "video", "description", "content")
+                WHERE e.id = ?
+                    AND e.current_revision_id IS NOT NULL
+            "#,
+            id
+        )
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        let entry = match entity {
+            Some(entity) => entity
+                .entity_type
+                .parse::<EntityType>()
+                .ok()
+                .and_then(|entity_type| serde_json::to_value(entity_type).ok())
+                .and_then(|typename| typename.as_str().map(String::from))
+                .map(|typename| SearchIndexEntry {
+                    instance_id: entity.instance_id as i32,
+                    typename,
+                    title: entity.title.unwrap_or_default(),
+                    meta_description: entity.meta_description.unwrap_or_default(),
+                    text: extract_plain_text(&entity.content.unwrap_or_default()),
+                }),
+            None => sqlx::query!(
+                r#"
+                    SELECT p.instance_id, r.title, r.content
+                    FROM page_repository p
+                    JOIN page_revision r ON r.id = p.current_revision_id
+                    WHERE p.id = ?
+                "#,
+                id
+            )
+            .fetch_optional(&mut *transaction)
+            .await?
+            .map(|page| SearchIndexEntry {
+                instance_id: page.instance_id as i32,
+                typename: "Page".to_string(),
+                title: page.title,
+                meta_description: "".to_string(),
+                text: extract_plain_text(&page.content),
+            }),
+        };
+
+        match entry {
+            Some(entry) => {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO search_index
+                            (uuid_id, instance_id, typename, title, meta_description, text)
+                            VALUES (?, ?, ?, ?, ?, ?)
+                            ON DUPLICATE KEY UPDATE
+                                instance_id = VALUES(instance_id),
+                                typename = VALUES(typename),
+                                title = VALUES(title),
+                                meta_description = VALUES(meta_description),
+                                text = VALUES(text)
+                    "#,
+                    id,
+                    entry.instance_id,
+                    entry.typename,
+                    entry.title,
+                    entry.meta_description,
+                    entry.text,
+                )
+                .execute(&mut *transaction)
+                .await?;
+            }
+            None => {
+                sqlx::query!(r#"DELETE FROM search_index WHERE uuid_id = ?"#, id)
+                    .execute(&mut *transaction)
+                    .await?;
+            }
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn rebuild<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &search_index_rebuild_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<search_index_rebuild_mutation::Output, operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        let mut ids: Vec<i32> = sqlx::query!(
+            r#"
+                SELECT searchable.id
+                FROM (
+                    SELECT e.id
+                    FROM entity e
+                    JOIN type ON type.id = e.type_id
+                    WHERE e.current_revision_id IS NOT NULL
+                        AND type.name IN ("applet", "article", "course", "course-page", "event",
+                            "text-exercise", "text-exercise-group", "video")
+                    UNION
+                    SELECT p.id
+                    FROM page_repository p
+                    WHERE p.current_revision_id IS NOT NULL
+                ) searchable
+                WHERE (? IS NULL OR searchable.id > ?)
+                ORDER BY searchable.id
+                LIMIT ?
+            "#,
+            payload.after,
+            payload.after,
+            payload.first + 1,
+        )
+        .fetch_all(&mut *transaction)
+        .await?
+        .into_iter()
+        .map(|record| record.id as i32)
+        .collect();
+
+        let has_next_page = ids.len() as i32 > payload.first;
+        ids.truncate(payload.first as usize);
+
+        for id in &ids {
+            Self::update(*id, &mut *transaction).await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(search_index_rebuild_mutation::Output {
+            success: true,
+            indexed_ids: ids,
+            has_next_page,
+        })
+    }
+
+    pub async fn search<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &search_query::Payload,
+        acquire_from: A,
+    ) -> Result<search_query::Output, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let types = payload
+            .types
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+
+        let records = sqlx::query!(
+            r#"
+                WITH results AS (
+                    SELECT
+                        s.uuid_id,
+                        s.typename,
+                        s.title,
+                        s.text,
+                        MATCH (s.title, s.meta_description, s.text)
+                            AGAINST (? IN NATURAL LANGUAGE MODE) AS score
+                    FROM search_index s
+                    JOIN uuid u ON u.id = s.uuid_id
+                    JOIN instance i ON i.id = s.instance_id
+                    WHERE MATCH (s.title, s.meta_description, s.text)
+                            AGAINST (? IN NATURAL LANGUAGE MODE)
+                        AND u.trashed = 0
+                        AND (? IS NULL OR i.subdomain = ?)
+                        AND (? IS NULL OR JSON_CONTAINS(?, JSON_QUOTE(s.typename)))
+                        AND (? IS NULL OR EXISTS (
+                            SELECT 1
+                            FROM term_taxonomy_entity tte
+                            JOIN subject_mapping
+                                ON subject_mapping.term_taxonomy_id = tte.term_taxonomy_id
+                            LEFT JOIN entity_link el ON el.parent_id = tte.entity_id
+                            WHERE (tte.entity_id = s.uuid_id OR el.child_id = s.uuid_id)
+                                AND subject_mapping.subject_id = ?
+                                AND subject_mapping.excluded = 0
+                        ))
+                )
+                SELECT uuid_id, typename, title, text
+                FROM results
+                WHERE ? IS NULL
+                    OR score < (SELECT score FROM results WHERE uuid_id = ?)
+                    OR (score = (SELECT score FROM results WHERE uuid_id = ?) AND uuid_id > ?)
+                ORDER BY score DESC, uuid_id
+                LIMIT ?
+            "#,
+            payload.query,
+            payload.query,
+            payload.instance,
+            payload.instance,
+            types,
+            types,
+            payload.subject_id,
+            payload.subject_id,
+            payload.after,
+            payload.after,
+            payload.after,
+            payload.after,
+            payload.first + 1,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let has_next_page = records.len() as i32 > payload.first;
+        let terms: Vec<&str> = payload.query.split_whitespace().collect();
+
+        let results = records
+            .into_iter()
+            .take(payload.first as usize)
+            .map(|record| {
+                let snippet = if record.text.is_empty() {
+                    create_snippet(&record.title, &terms)
+                } else {
+                    create_snippet(&record.text, &terms)
+                };
+
+                search_query::SearchResult {
+                    id: record.uuid_id as i32,
+                    typename: record.typename,
+                    title: record.title,
+                    snippet,
+                }
+            })
+            .collect();
+
+        Ok(search_query::Output {
+            results,
+            has_next_page,
+        })
+    }
+}
+
+/// Returns the plain text of an editor state by collecting all `text` leaves. Content which is no
+/// editor state (e.g. legacy markdown) is returned unchanged.
+pub fn extract_plain_text(content: &str) -> String {
+    match serde_json::from_str::<Value>(content) {
+        Ok(document) if document.is_object() || document.is_array() => {
+            let mut texts = Vec::new();
+            collect_texts(&document, &mut texts);
+            texts.join(" ")
+        }
+        _ => content.to_string(),
+    }
+}
+
+fn collect_texts(value: &Value, texts: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match (key.as_str(), value) {
+                    ("text", Value::String(text)) if !text.trim().is_empty() => {
+                        texts.push(text.trim().to_string())
+                    }
+                    _ => collect_texts(value, texts),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_texts(value, texts)),
+        _ => {}
+    }
+}
+
+/// Cuts a snippet out of `text` around the first match of one of the search terms. All matches
+/// inside the snippet are wrapped in `<mark>` tags.
+pub fn create_snippet(text: &str, terms: &[&str]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lowercase_chars: Vec<char> = chars.iter().map(|c| to_lowercase_char(*c)).collect();
+    let mut terms: Vec<Vec<char>> = terms
+        .iter()
+        .map(|term| {
+            term.chars()
+                .filter(|c| c.is_alphanumeric())
+                .map(to_lowercase_char)
+                .collect::<Vec<char>>()
+        })
+        .filter(|term| !term.is_empty())
+        .collect();
+    terms.sort_by_key(|term| std::cmp::Reverse(term.len()));
+
+    let mut matches = Vec::new();
+    let mut index = 0;
+
+    while index < lowercase_chars.len() {
+        match terms
+            .iter()
+            .find(|term| lowercase_chars[index..].starts_with(term))
+        {
+            Some(term) => {
+                matches.push((index, index + term.len()));
+                index += term.len();
+            }
+            None => index += 1,
+        }
+    }
+
+    let start = matches
+        .first()
+        .map(|(start, _)| start.saturating_sub(SNIPPET_CONTEXT))
+        .unwrap_or(0);
+    let end = chars.len().min(start + SNIPPET_LENGTH);
+
+    let mut snippet = String::new();
+
+    if start > 0 {
+        snippet.push('…');
+    }
+
+    let mut index = start;
+    for (match_start, match_end) in matches
+        .iter()
+        .filter(|(match_start, match_end)| *match_start >= start && *match_end <= end)
+    {
+        snippet.extend(&chars[index..*match_start]);
+        snippet.push_str("<mark>");
+        snippet.extend(&chars[*match_start..*match_end]);
+        snippet.push_str("</mark>");
+        index = *match_end;
+    }
+    snippet.extend(&chars[index..end]);
+
+    if end < chars.len() {
+        snippet.push('…');
+    }
+
+    snippet
+}
+
+fn to_lowercase_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_snippet, extract_plain_text};
+
+    #[test]
+    fn extracts_plain_text_from_editor_state() {
+        let content = r#"{"plugin":"rows","state":[{"plugin":"text","state":[
+            {"type":"p","children":[{"text":"Hello"},{"text":"world","strong":true}]}
+        ]}]}"#;
+
+        assert_eq!(extract_plain_text(content), "Hello world");
+    }
+
+    #[test]
+    fn keeps_legacy_content() {
+        assert_eq!(
+            extract_plain_text("## Legacy markdown"),
+            "## Legacy markdown"
+        );
+    }
+
+    #[test]
+    fn highlights_matches_case_insensitively() {
+        assert_eq!(
+            create_snippet("Die Ableitung einer Funktion", &["ableitung"]),
+            "Die <mark>Ableitung</mark> einer Funktion"
+        );
+    }
+
+    #[test]
+    fn cuts_snippet_around_first_match() {
+        let text = format!("{} Größe {}", "a".repeat(100), "b".repeat(200));
+        let snippet = create_snippet(&text, &["größe"]);
+
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>Größe</mark>"));
+    }
+}
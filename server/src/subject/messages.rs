@@ -0,0 +1,289 @@
+use actix_web::HttpResponse;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::model::{SubjectRegistryEntry, SubjectRegistryKind};
+use crate::message::MessageResponder;
+use crate::operation::{self, Operation, SuccessOutput};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum SubjectsMessage {
+    SubjectsQuery(Option<subjects_query::Payload>),
+    SubjectRegistryQuery(subject_registry_query::Payload),
+    SubjectRegistrySetMutation(subject_registry_set_mutation::Payload),
+    SubjectRegistryRemoveMutation(subject_registry_remove_mutation::Payload),
+}
+
+#[async_trait]
+impl MessageResponder for SubjectsMessage {
+    async fn handle<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+        &self,
+        acquire_from: A,
+    ) -> HttpResponse {
+        match self {
+            SubjectsMessage::SubjectsQuery(payload) => {
+                let payload = payload.clone().unwrap_or_default();
+                payload.handle(acquire_from).await
+            }
+            SubjectsMessage::SubjectRegistryQuery(payload) => payload.handle(acquire_from).await,
+            SubjectsMessage::SubjectRegistrySetMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+            SubjectsMessage::SubjectRegistryRemoveMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+        }
+    }
+}
+
+pub mod subjects_query {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::format_alias;
+    use crate::instance::Instance;
+    use crate::uuid::EntityType;
+
+    #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub instance: Option<Instance>,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub subjects: Vec<Subject>,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Subject {
+        pub instance: String,
+        pub taxonomy_term_id: i32,
+        pub name: String,
+        pub alias: String,
+        pub description: Option<String>,
+        pub entity_counts: HashMap<String, i32>,
+        pub unrevised_revision_count: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Ok(fetch_subjects(self, acquire_from).await?)
+        }
+    }
+
+    async fn fetch_subjects<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &Payload,
+        acquire_from: A,
+    ) -> Result<subjects_query::Output, sqlx::Error> {
+        let mut connection = acquire_from.acquire().await?;
+
+        // Entities are counted for the subject of the taxonomy terms they are linked to, child
+        // entities (like course pages) for the subject of their parent. Only the subjects of the
+        // requested instance are counted.
+        let records = sqlx::query!(
+            r#"
+                WITH instance_subject_mapping AS (
+                    SELECT subject_mapping.term_taxonomy_id, subject_mapping.subject_id
+                    FROM subject_mapping
+                    JOIN term_taxonomy subject ON subject.id = subject_mapping.subject_id
+                    JOIN term subject_term ON subject_term.id = subject.term_id
+                    JOIN instance subject_instance ON subject_instance.id = subject_term.instance_id
+                    WHERE subject_mapping.excluded = 0
+                        AND (? IS NULL OR subject_instance.subdomain = ?)
+                ),
+                subject_entities AS (
+                    SELECT instance_subject_mapping.subject_id, tte.entity_id
+                    FROM term_taxonomy_entity tte
+                    JOIN instance_subject_mapping
+                        ON instance_subject_mapping.term_taxonomy_id = tte.term_taxonomy_id
+
+                    UNION
+
+                    SELECT instance_subject_mapping.subject_id, el.child_id
+                    FROM term_taxonomy_entity tte
+                    JOIN instance_subject_mapping
+                        ON instance_subject_mapping.term_taxonomy_id = tte.term_taxonomy_id
+                    JOIN entity_link el ON el.parent_id = tte.entity_id
+                )
+                SELECT
+                    subject_entities.subject_id,
+                    type.name AS entity_type,
+                    COUNT(DISTINCT e.id) AS entity_count,
+                    COUNT(r.id) AS unrevised_revision_count
+                FROM subject_entities
+                JOIN entity e ON e.id = subject_entities.entity_id
+                JOIN uuid u_e ON u_e.id = e.id
+                JOIN type ON type.id = e.type_id
+                LEFT JOIN (
+                    entity_revision r
+                    JOIN uuid u_r ON u_r.id = r.id AND u_r.trashed = 0
+                ) ON r.repository_id = e.id
+                    AND (e.current_revision_id IS NULL OR r.id > e.current_revision_id)
+                WHERE u_e.trashed = 0
+                GROUP BY subject_entities.subject_id, type.name
+            "#,
+            payload.instance,
+            payload.instance,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let mut entity_counts: HashMap<i32, HashMap<String, i32>> = HashMap::new();
+        let mut unrevised_revision_counts: HashMap<i32, i32> = HashMap::new();
+
+        for record in records {
+            let subject_id = record.subject_id as i32;
+
+            if let Ok(entity_type) = record.entity_type.parse::<EntityType>() {
+                entity_counts
+                    .entry(subject_id)
+                    .or_default()
+                    .insert(format!("{entity_type:?}"), record.entity_count as i32);
+            }
+            *unrevised_revision_counts.entry(subject_id).or_insert(0) +=
+                record.unrevised_revision_count as i32;
+        }
+
+        let subjects = sqlx::query!(
+            r#"
+                SELECT
+                    subject.id,
+                    subject_instance.subdomain as instance,
+                    subject_term.name,
+                    subject.description
+                FROM term_taxonomy AS subject
+                JOIN term_taxonomy AS root ON root.id = subject.parent_id
+                JOIN uuid as subject_uuid ON subject_uuid.id = subject.id
+                JOIN taxonomy AS subject_taxonomy ON subject_taxonomy.id = subject.taxonomy_id
+                JOIN type AS subject_type ON subject_type.id = subject_taxonomy.type_id
+                JOIN term AS subject_term ON subject_term.id = subject.term_id
+                JOIN instance AS subject_instance ON subject_instance.id = subject_term.instance_id
+                WHERE
+                    (root.parent_id IS NULL
+                      OR root.id IN (
+                          SELECT term_taxonomy_id FROM subject_registry
+                          WHERE kind IN ("in-construction", "partner-root")
+                      ))
+                    AND subject_uuid.trashed = 0
+                    AND (subject_type.name = "subject" or subject_type.name = "topic")
+                    AND (? IS NULL OR subject_instance.subdomain = ?)
+                ORDER BY subject.id;
+
+            "#,
+            payload.instance,
+            payload.instance,
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|record| {
+            let taxonomy_term_id = record.id as i32;
+
+            subjects_query::Subject {
+                taxonomy_term_id,
+                instance: record.instance,
+                alias: format_alias(Some(&record.name), taxonomy_term_id, Some(&record.name)),
+                name: record.name,
+                description: record.description,
+                entity_counts: entity_counts.remove(&taxonomy_term_id).unwrap_or_default(),
+                unrevised_revision_count: unrevised_revision_counts
+                    .get(&taxonomy_term_id)
+                    .copied()
+                    .unwrap_or(0),
+            }
+        })
+        .collect();
+
+        Ok(subjects_query::Output { subjects })
+    }
+}
+
+pub mod subject_registry_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub kind: Option<SubjectRegistryKind>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub entries: Vec<SubjectRegistryEntry>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Ok(Output {
+                entries: SubjectRegistryEntry::fetch_all(&self.kind, acquire_from).await?,
+            })
+        }
+    }
+}
+
+pub mod subject_registry_set_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub taxonomy_term_id: i32,
+        pub kind: SubjectRegistryKind,
+        #[serde(default)]
+        pub amb_subjects: Vec<String>,
+        pub thumbnail: Option<String>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            SubjectRegistryEntry::set(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
+
+pub mod subject_registry_remove_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub taxonomy_term_id: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            SubjectRegistryEntry::remove(self.taxonomy_term_id, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::messages::subject_registry_set_mutation;
+use crate::operation;
+use crate::uuid::{AssertExists, TaxonomyTerm};
+
+const SCHOOL_SUBJECT_PREFIX: &str = "http://w3id.org/kim/schulfaecher/";
+const UNIVERSITY_SUBJECT_PREFIX: &str = "https://w3id.org/kim/hochschulfaechersystematik/";
+
+/// Taxonomy terms with a special role when content is mapped to subjects.
+///
+/// The entries are stored in the table `subject_registry` which is created and seeded by
+/// `migrations/20261019080500_create_subject_registry.sql`. `amb_subjects` is a JSON array of AMB
+/// subject URIs and `thumbnail` is the file name of the meta image.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectRegistryEntry {
+    pub taxonomy_term_id: i32,
+    pub kind: SubjectRegistryKind,
+    pub amb_subjects: Vec<String>,
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubjectRegistryKind {
+    // Metadata (AMB subjects and thumbnail) of a subject
+    Subject,
+    // Containers like "Fächer im Aufbau" whose children are subjects
+    InConstruction,
+    // Containers like "Mathematik > Partner" whose children are subjects, the container itself is
+    // never a subject
+    PartnerRoot,
+    // Content below the children of this term is not attributed to the surrounding subject
+    Detached,
+    // Test areas like "Baustelle" or "Testbereich" whose content belongs to no subject
+    Excluded,
+}
+
+impl std::str::FromStr for SubjectRegistryKind {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::value::Value::String(s.to_string()))
+    }
+}
+
+impl fmt::Display for SubjectRegistryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decoded = serde_json::to_value(self).unwrap();
+        let decoded = decoded.as_str().unwrap();
+        write!(f, "{decoded}")
+    }
+}
+
+impl SubjectRegistryEntry {
+    pub async fn fetch_all<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        kind: &Option<SubjectRegistryKind>,
+        acquire_from: A,
+    ) -> Result<Vec<Self>, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let kind = kind.as_ref().map(|kind| kind.to_string());
+
+        sqlx::query!(
+            r#"
+                SELECT term_taxonomy_id, kind, amb_subjects, thumbnail
+                FROM subject_registry
+                WHERE (? IS NULL OR kind = ?)
+                ORDER BY term_taxonomy_id
+            "#,
+            kind,
+            kind
+        )
+        .fetch_all(&mut *connection)
+        .await?
+        .into_iter()
+        .map(|record| {
+            Ok(SubjectRegistryEntry {
+                taxonomy_term_id: record.term_taxonomy_id as i32,
+                kind: record.kind.parse()?,
+                amb_subjects: record
+                    .amb_subjects
+                    .map(serde_json::from_value)
+                    .transpose()?
+                    .unwrap_or_default(),
+                thumbnail: record.thumbnail,
+            })
+        })
+        .collect()
+    }
+
+    pub async fn set<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &subject_registry_set_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        TaxonomyTerm::assert_exists(payload.taxonomy_term_id, &mut *transaction).await?;
+
+        if payload.kind != SubjectRegistryKind::Subject
+            && (!payload.amb_subjects.is_empty() || payload.thumbnail.is_some())
+        {
+            return Err(operation::Error::BadRequest {
+                reason: "only entries of kind `subject` can have AMB subjects or a thumbnail"
+                    .to_string(),
+            });
+        }
+
+        if let Some(uri) = payload
+            .amb_subjects
+            .iter()
+            .find(|uri| amb_scheme(uri).is_none())
+        {
+            return Err(operation::Error::BadRequest {
+                reason: format!("`{uri}` is not a known AMB subject"),
+            });
+        }
+
+        if payload
+            .thumbnail
+            .as_ref()
+            .is_some_and(|thumbnail| thumbnail.is_empty() || thumbnail.contains('/'))
+        {
+            return Err(operation::Error::BadRequest {
+                reason: "thumbnail must be a file name".to_string(),
+            });
+        }
+
+        sqlx::query!(
+            r#"
+                INSERT INTO subject_registry (term_taxonomy_id, kind, amb_subjects, thumbnail)
+                    VALUES (?, ?, ?, ?)
+                    ON DUPLICATE KEY UPDATE
+                        kind = VALUES(kind),
+                        amb_subjects = VALUES(amb_subjects),
+                        thumbnail = VALUES(thumbnail)
+            "#,
+            payload.taxonomy_term_id,
+            payload.kind.to_string(),
+            serde_json::to_string(&payload.amb_subjects)?,
+            payload.thumbnail,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn remove<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        taxonomy_term_id: i32,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+
+        let result = sqlx::query!(
+            r#"DELETE FROM subject_registry WHERE term_taxonomy_id = ?"#,
+            taxonomy_term_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(operation::Error::BadRequest {
+                reason: format!("Taxonomy term {taxonomy_term_id} is not in the subject registry"),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the concept scheme of an AMB subject URI like
+/// `http://w3id.org/kim/schulfaecher/s1017`.
+pub fn amb_scheme(uri: &str) -> Option<&'static str> {
+    let (scheme, id) = if let Some(id) = uri.strip_prefix(SCHOOL_SUBJECT_PREFIX) {
+        (SCHOOL_SUBJECT_PREFIX, id.strip_prefix('s')?)
+    } else if let Some(id) = uri.strip_prefix(UNIVERSITY_SUBJECT_PREFIX) {
+        (
+            "https://w3id.org/kim/hochschulfaechersystematik/scheme",
+            id.strip_prefix('n')?,
+        )
+    } else {
+        return None;
+    };
+
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+        Some(scheme)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::amb_scheme;
+
+    #[test]
+    fn returns_scheme_of_amb_subjects() {
+        assert_eq!(
+            amb_scheme("http://w3id.org/kim/schulfaecher/s1017"),
+            Some("http://w3id.org/kim/schulfaecher/")
+        );
+        assert_eq!(
+            amb_scheme("https://w3id.org/kim/hochschulfaechersystematik/n294"),
+            Some("https://w3id.org/kim/hochschulfaechersystematik/scheme")
+        );
+        assert_eq!(amb_scheme("http://w3id.org/kim/schulfaecher/1017"), None);
+        assert_eq!(amb_scheme("https://example.org/s1017"), None);
+    }
+}
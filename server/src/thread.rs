@@ -1,9 +1,12 @@
 use crate::datetime::DateTime;
 use crate::event::CreateThreadEventPayload;
-use crate::event::{CreateCommentEventPayload, SetThreadStateEventPayload};
+use crate::event::{CreateCommentEventPayload, MoveThreadEventPayload, SetThreadStateEventPayload};
+use crate::instance::Instance;
 use crate::operation::{self, Operation};
 use crate::subscription::Subscription;
-use crate::uuid::{CommentStatus, Uuid, UuidFetcher};
+use crate::uuid::{
+    AssertExists, Comment, CommentHistoryEntry, CommentStatus, Mention, User, Uuid, UuidFetcher,
+};
 use actix_web::HttpResponse;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -15,11 +18,15 @@ use crate::message::MessageResponder;
 #[serde(tag = "type", content = "payload")]
 pub enum ThreadMessage {
     ThreadsQuery(threads_query::Payload),
+    AllThreadsQuery(all_threads_query::Payload),
     ThreadCreateThreadMutation(create_thread_mutation::Payload),
     ThreadCreateCommentMutation(create_comment_mutation::Payload),
     ThreadSetThreadArchivedMutation(set_thread_archived_mutation::Payload),
     ThreadSetThreadStatusMutation(set_thread_state_mutation::Payload),
     ThreadEditCommentMutation(edit_comment_mutation::Payload),
+    CommentHistoryQuery(comment_history_query::Payload),
+    ThreadMoveMutation(move_thread_mutation::Payload),
+    MentionsQuery(mentions_query::Payload),
 }
 
 #[async_trait]
@@ -30,6 +37,7 @@ impl MessageResponder for ThreadMessage {
     ) -> HttpResponse {
         match self {
             ThreadMessage::ThreadsQuery(message) => message.handle(acquire_from).await,
+            ThreadMessage::AllThreadsQuery(message) => message.handle(acquire_from).await,
             ThreadMessage::ThreadCreateThreadMutation(message) => {
                 message.handle(acquire_from).await
             }
@@ -43,6 +51,9 @@ impl MessageResponder for ThreadMessage {
                 message.handle(acquire_from).await
             }
             ThreadMessage::ThreadEditCommentMutation(message) => message.handle(acquire_from).await,
+            ThreadMessage::CommentHistoryQuery(message) => message.handle(acquire_from).await,
+            ThreadMessage::ThreadMoveMutation(message) => message.handle(acquire_from).await,
+            ThreadMessage::MentionsQuery(message) => message.handle(acquire_from).await,
         }
     }
 }
@@ -85,6 +96,154 @@ pub mod threads_query {
     }
 }
 
+pub mod all_threads_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub after: Option<i32>,
+        pub object_id: Option<i32>,
+        pub instance: Option<Instance>,
+        pub subject_id: Option<i32>,
+        pub status: Option<CommentStatus>,
+        pub archived: Option<bool>,
+        pub has_replies: Option<bool>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub first_comment_ids: Vec<i32>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            let mut connection = acquire_from.acquire().await?;
+            let status = self.status.as_ref().map(|status| status.to_string());
+
+            // Threads are sorted by their last activity, which is the date of the latest
+            // non-trashed reply or the date of the thread itself. The cursor `after` is the id
+            // of the last thread of the previous page.
+            let result = sqlx::query!(
+                r#"
+                    WITH subject_entities AS (
+                        SELECT tte.entity_id
+                        FROM term_taxonomy_entity tte
+                        JOIN subject_mapping ON subject_mapping.term_taxonomy_id = tte.term_taxonomy_id
+                        WHERE subject_mapping.subject_id = ? AND subject_mapping.excluded = 0
+
+                        UNION
+
+                        SELECT el.child_id
+                        FROM term_taxonomy_entity tte
+                        JOIN subject_mapping ON subject_mapping.term_taxonomy_id = tte.term_taxonomy_id
+                        JOIN entity_link el ON el.parent_id = tte.entity_id
+                        WHERE subject_mapping.subject_id = ? AND subject_mapping.excluded = 0
+                    ),
+                    subject_objects AS (
+                        SELECT term_taxonomy_id AS id
+                        FROM subject_mapping
+                        WHERE subject_id = ? AND excluded = 0
+                        UNION
+                        SELECT entity_id FROM subject_entities
+                        UNION
+                        SELECT er.id
+                        FROM entity_revision er
+                        JOIN subject_entities ON subject_entities.entity_id = er.repository_id
+                    ),
+                    threads AS (
+                        SELECT
+                            comment.id,
+                            GREATEST(comment.date, IFNULL(MAX(reply.date), comment.date)) AS last_activity,
+                            COUNT(reply.id) AS reply_count
+                        FROM comment
+                        JOIN instance ON instance.id = comment.instance_id
+                        LEFT JOIN comment_status ON comment_status.id = comment.comment_status_id
+                        LEFT JOIN (
+                            SELECT reply.id, reply.parent_id, reply.date
+                            FROM comment reply
+                            JOIN uuid ON uuid.id = reply.id
+                            WHERE uuid.trashed = 0
+                        ) reply ON reply.parent_id = comment.id
+                        WHERE comment.uuid_id IS NOT NULL
+                            AND (? IS NULL OR comment.uuid_id = ?)
+                            AND (? IS NULL OR instance.subdomain = ?)
+                            AND (? IS NULL OR comment.archived = ?)
+                            AND (? IS NULL OR IFNULL(comment_status.name, "no_status") = ?)
+                            AND (? IS NULL OR comment.uuid_id IN (SELECT id FROM subject_objects))
+                        GROUP BY comment.id, comment.date
+                    ),
+                    cursor_thread AS (
+                        SELECT
+                            comment.id,
+                            GREATEST(comment.date, IFNULL(MAX(reply.date), comment.date)) AS last_activity
+                        FROM comment
+                        LEFT JOIN (
+                            SELECT reply.parent_id, reply.date
+                            FROM comment reply
+                            JOIN uuid ON uuid.id = reply.id
+                            WHERE uuid.trashed = 0
+                        ) reply ON reply.parent_id = comment.id
+                        WHERE comment.id = ?
+                        GROUP BY comment.id, comment.date
+                    )
+                    SELECT threads.id
+                    FROM threads
+                    WHERE (? IS NULL OR (threads.reply_count > 0) = ?)
+                        AND (
+                            ? IS NULL
+                            OR (threads.last_activity, threads.id)
+                                < (SELECT last_activity, id FROM cursor_thread)
+                        )
+                    ORDER BY threads.last_activity DESC, threads.id DESC
+                    LIMIT ?
+                "#,
+                self.subject_id,
+                self.subject_id,
+                self.subject_id,
+                self.object_id,
+                self.object_id,
+                self.instance,
+                self.instance,
+                self.archived,
+                self.archived,
+                status,
+                status,
+                self.subject_id,
+                self.after,
+                self.has_replies,
+                self.has_replies,
+                self.after,
+                self.first + 1,
+            )
+            .fetch_all(&mut *connection)
+            .await?;
+
+            let has_next_page = result.len() as i32 > self.first;
+
+            Ok(Output {
+                first_comment_ids: result
+                    .iter()
+                    .take(self.first as usize)
+                    .map(|thread| thread.id as i32)
+                    .collect(),
+                has_next_page,
+            })
+        }
+    }
+}
+
 pub mod create_thread_mutation {
     use super::*;
 
@@ -172,6 +331,9 @@ pub mod create_thread_mutation {
                 .await?;
             let thread_id = value.id as i32;
 
+            Comment::save_mentions(thread_id, self.user_id, &self.content, &mut *transaction)
+                .await?;
+
             CreateThreadEventPayload::new(thread_id, self.object_id, self.user_id, instance_id)
                 .save(&mut *transaction)
                 .await
@@ -274,6 +436,9 @@ pub mod create_comment_mutation {
                 .await?;
             let comment_id = value.id as i32;
 
+            Comment::save_mentions(comment_id, self.user_id, &self.content, &mut *transaction)
+                .await?;
+
             CreateCommentEventPayload::new(
                 self.thread_id,
                 comment_id,
@@ -493,13 +658,24 @@ pub mod edit_comment_mutation {
 
             if self.content != comment.content.as_deref().unwrap_or("") {
                 sqlx::query!(
-                    // todo: update edit_date (after database migration)
-                    // UPDATE comment SET content = ?, edit_date = ? WHERE id = ?
                     r#"
-                    UPDATE comment SET content = ? WHERE id = ?
+                        INSERT INTO comment_history (comment_id, content, editor_id, date)
+                            VALUES (?, ?, ?, ?)
+                    "#,
+                    self.comment_id,
+                    comment.content.as_deref().unwrap_or(""),
+                    self.user_id,
+                    DateTime::now(),
+                )
+                .execute(&mut *transaction)
+                .await?;
+
+                sqlx::query!(
+                    r#"
+                    UPDATE comment SET content = ?, edit_date = ? WHERE id = ?
                 "#,
                     self.content,
-                    // DateTime::now(),
+                    DateTime::now(),
                     self.comment_id,
                 )
                 .execute(&mut *transaction)
@@ -512,3 +688,198 @@ pub mod edit_comment_mutation {
         }
     }
 }
+
+pub mod comment_history_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub id: i32,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub history: Vec<CommentHistoryEntry>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Ok(Output {
+                history: Comment::fetch_history(self.id, acquire_from).await?,
+            })
+        }
+    }
+}
+
+pub mod move_thread_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub thread_id: i32,
+        pub new_object_id: i32,
+        pub user_id: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = operation::SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            let mut transaction = acquire_from.begin().await?;
+
+            User::assert_exists(self.user_id, &mut *transaction).await?;
+
+            let thread = sqlx::query!(
+                "SELECT uuid_id, instance_id FROM comment WHERE id = ? AND uuid_id IS NOT NULL",
+                self.thread_id
+            )
+            .fetch_optional(&mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: "thread does not exist".to_string(),
+            })?;
+            let previous_object_id = thread.uuid_id.unwrap() as i32;
+
+            if previous_object_id == self.new_object_id {
+                return Err(operation::Error::BadRequest {
+                    reason: "thread is already attached to the given object".to_string(),
+                });
+            }
+
+            // Threads can only be attached to objects a user can visit. Users belong to no
+            // instance, so threads on user profiles keep the instance of the thread.
+            let new_object = sqlx::query!(
+                r#"
+                    SELECT u.trashed, o.instance_id AS "instance_id?"
+                        FROM uuid u
+                        LEFT JOIN (
+                            SELECT id, instance_id FROM entity
+                            UNION ALL
+                            SELECT id, instance_id FROM page_repository
+                            UNION ALL
+                            SELECT ta.id, t.instance_id FROM term_taxonomy ta JOIN term t ON t.id = ta.term_id
+                        ) o ON o.id = u.id
+                        WHERE u.id = ?
+                            AND u.discriminator IN ("entity", "page", "taxonomyTerm", "user")
+                "#,
+                self.new_object_id
+            )
+            .fetch_optional(&mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: "threads can only be moved to entities, pages, taxonomy terms or users"
+                    .to_string(),
+            })?;
+
+            if new_object.trashed != 0 {
+                return Err(operation::Error::BadRequest {
+                    reason: "thread cannot be moved to a trashed object".to_string(),
+                });
+            }
+
+            let instance_id = new_object.instance_id.unwrap_or(thread.instance_id);
+
+            sqlx::query!(
+                "UPDATE comment SET uuid_id = ?, instance_id = ? WHERE id = ?",
+                self.new_object_id,
+                instance_id,
+                self.thread_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE comment SET instance_id = ? WHERE parent_id = ?",
+                instance_id,
+                self.thread_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            // Subscriptions to the thread are stored for the thread itself, so they move together
+            // with it. Subscribers of the thread who followed the previous object follow the new
+            // one, an existing subscription to the new object keeps its email setting.
+            sqlx::query!(
+                r#"
+                    INSERT INTO subscription (uuid_id, user_id, notify_mailman, date)
+                        SELECT ?, object_subscription.user_id,
+                            object_subscription.notify_mailman, ?
+                        FROM subscription object_subscription
+                        JOIN subscription thread_subscription
+                            ON thread_subscription.user_id = object_subscription.user_id
+                        WHERE object_subscription.uuid_id = ?
+                            AND thread_subscription.uuid_id = ?
+                    ON DUPLICATE KEY UPDATE notify_mailman = subscription.notify_mailman
+                "#,
+                self.new_object_id,
+                DateTime::now(),
+                previous_object_id,
+                self.thread_id,
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            MoveThreadEventPayload::new(
+                self.thread_id,
+                previous_object_id,
+                self.new_object_id,
+                self.user_id,
+                instance_id,
+            )
+            .save(&mut *transaction)
+            .await
+            .map_err(|error| operation::Error::InternalServerError {
+                error: Box::new(error),
+            })?;
+
+            transaction.commit().await?;
+
+            Ok(operation::SuccessOutput { success: true })
+        }
+    }
+}
+
+pub mod mentions_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub user_id: i32,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub mentions: Vec<Mention>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            let mut connection = acquire_from.acquire().await?;
+
+            Ok(Output {
+                mentions: Comment::fetch_mentions(self.user_id, &mut *connection).await?,
+            })
+        }
+    }
+}
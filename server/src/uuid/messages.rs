@@ -10,6 +10,7 @@ use crate::message::MessageResponder;
 #[serde(tag = "type", content = "payload")]
 pub enum UuidMessage {
     UuidQuery(uuid_query::Payload),
+    UuidPathsQuery(uuid_paths_query::Payload),
     UuidSetStateMutation(uuid_set_state_mutation::Payload),
 }
 
@@ -21,6 +22,7 @@ impl MessageResponder for UuidMessage {
     ) -> HttpResponse {
         match self {
             UuidMessage::UuidQuery(message) => message.handle(acquire_from).await,
+            UuidMessage::UuidPathsQuery(message) => message.handle(acquire_from).await,
             UuidMessage::UuidSetStateMutation(message) => message.handle(acquire_from).await,
         }
     }
@@ -48,6 +50,48 @@ pub mod uuid_query {
     }
 }
 
+pub mod uuid_paths_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub id: i32,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct Output {
+        pub paths: Vec<Path>,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct Path {
+        pub canonical: bool,
+        pub nodes: Vec<Node>,
+    }
+
+    #[derive(Clone, Debug, Serialize)]
+    pub struct Node {
+        pub id: i32,
+        #[serde(rename = "__typename")]
+        pub typename: String,
+        pub name: Option<String>,
+        pub trashed: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Uuid::fetch_paths(self.id, acquire_from).await
+        }
+    }
+}
+
 pub mod uuid_set_state_mutation {
     use super::*;
 
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use super::{ConcreteUuid, Uuid, UuidError, UuidFetcher};
@@ -8,6 +9,7 @@ use crate::datetime::DateTime;
 use crate::format_alias;
 
 use std::str::FromStr;
+use std::sync::OnceLock;
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +24,29 @@ pub struct Comment {
     pub parent_id: i32,
     pub children_ids: Vec<i32>,
     pub status: CommentStatus,
+    pub edited: bool,
+    pub last_edited_at: Option<DateTime>,
+}
+
+/// A prior version of a comment's content. Whenever a comment is edited, the replaced content is
+/// stored in the table `comment_history` (columns `id`, `comment_id`, `content`, `editor_id` and
+/// `date` of the edit).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentHistoryEntry {
+    pub content: String,
+    pub editor_id: i32,
+    pub date: DateTime,
+}
+
+/// A user mentioned with `@username` in a comment. Mentions are stored in the table
+/// `comment_mention` with the columns `comment_id` and `user_id` (together the primary key).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mention {
+    pub comment_id: i32,
+    pub author_id: i32,
+    pub date: DateTime,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -60,7 +85,8 @@ macro_rules! fetch_one_comment {
        sqlx::query!(
             r#"
                 SELECT u.trashed, c.author_id, c.title, c.date, c.archived, c.content, c.parent_id,
-                        c.uuid_id, p.title as parent_title, comment_status.name as status
+                        c.uuid_id, p.title as parent_title, comment_status.name as status,
+                        c.edit_date AS last_edited_at
                     FROM comment c
                     LEFT JOIN comment p ON p.id = c.parent_id
                     LEFT JOIN comment_status ON comment_status.id = c.comment_status_id
@@ -124,6 +150,8 @@ macro_rules! to_comment {
                 content: comment.content.unwrap_or_else(|| "".to_string()),
                 parent_id: comment.parent_id.or(comment.uuid_id).unwrap() as i32,
                 children_ids: children.iter().map(|child| child.id as i32).collect(),
+                edited: comment.last_edited_at.is_some(),
+                last_edited_at: comment.last_edited_at.map(|date| date.into()),
             }),
         })
     }};
@@ -173,4 +201,156 @@ impl Comment {
         transaction.commit().await?;
         Ok(context)
     }
+
+    pub async fn fetch_history<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        id: i32,
+        acquire_from: A,
+    ) -> Result<Vec<CommentHistoryEntry>, UuidError> {
+        let mut transaction = acquire_from.begin().await?;
+
+        sqlx::query!(r#"SELECT id FROM comment WHERE id = ?"#, id)
+            .fetch_optional(&mut *transaction)
+            .await?
+            .ok_or(UuidError::NotFound)?;
+
+        let history = sqlx::query!(
+            r#"
+                SELECT content, editor_id, date
+                    FROM comment_history
+                    WHERE comment_id = ?
+                    ORDER BY date DESC, id DESC
+            "#,
+            id
+        )
+        .fetch_all(&mut *transaction)
+        .await?
+        .into_iter()
+        .map(|entry| CommentHistoryEntry {
+            content: entry.content,
+            editor_id: entry.editor_id as i32,
+            date: entry.date.into(),
+        })
+        .collect();
+
+        transaction.commit().await?;
+
+        Ok(history)
+    }
+
+    /// Stores the users mentioned in the content of the comment and returns their ids. Unknown
+    /// usernames and mentions of the author are ignored.
+    pub async fn save_mentions<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        comment_id: i32,
+        author_id: i32,
+        content: &str,
+        acquire_from: A,
+    ) -> Result<Vec<i32>, sqlx::Error> {
+        let mut transaction = acquire_from.begin().await?;
+        let mut user_ids = Vec::new();
+
+        for username in parse_mentions(content) {
+            let user = sqlx::query!(r#"SELECT id FROM user WHERE username = ?"#, username)
+                .fetch_optional(&mut *transaction)
+                .await?;
+
+            if let Some(user) = user {
+                let user_id = user.id as i32;
+
+                if user_id != author_id && !user_ids.contains(&user_id) {
+                    sqlx::query!(
+                        r#"
+                            INSERT INTO comment_mention (comment_id, user_id)
+                                VALUES (?, ?)
+                        "#,
+                        comment_id,
+                        user_id
+                    )
+                    .execute(&mut *transaction)
+                    .await?;
+                    user_ids.push(user_id);
+                }
+            }
+        }
+
+        transaction.commit().await?;
+
+        Ok(user_ids)
+    }
+
+    pub async fn fetch_mentioned_user_ids<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        comment_id: i32,
+        executor: E,
+    ) -> Result<Vec<i32>, sqlx::Error> {
+        Ok(sqlx::query!(
+            r#"SELECT user_id FROM comment_mention WHERE comment_id = ?"#,
+            comment_id
+        )
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .map(|mention| mention.user_id as i32)
+        .collect())
+    }
+
+    pub async fn fetch_mentions<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        user_id: i32,
+        executor: E,
+    ) -> Result<Vec<Mention>, sqlx::Error> {
+        Ok(sqlx::query!(
+            r#"
+                SELECT m.comment_id, c.author_id, c.date
+                    FROM comment_mention m
+                    JOIN comment c ON c.id = m.comment_id
+                    JOIN uuid u ON u.id = m.comment_id
+                    WHERE m.user_id = ? AND u.trashed = 0
+                    ORDER BY c.date DESC, m.comment_id DESC
+            "#,
+            user_id
+        )
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .map(|mention| Mention {
+            comment_id: mention.comment_id as i32,
+            author_id: mention.author_id as i32,
+            date: mention.date.into(),
+        })
+        .collect())
+    }
+}
+
+/// Returns the usernames mentioned with `@username` in the given content. An `@` directly
+/// following a word character (like in an email address) is not a mention.
+pub fn parse_mentions(content: &str) -> Vec<String> {
+    static MENTION: OnceLock<Regex> = OnceLock::new();
+    let regex =
+        MENTION.get_or_init(|| Regex::new(r"(?:^|[^\w@.])@([\w\-]+(?:\.[\w\-]+)*)").unwrap());
+    let mut usernames: Vec<String> = Vec::new();
+
+    for captures in regex.captures_iter(content) {
+        let username = captures[1].to_string();
+        if !usernames.contains(&username) {
+            usernames.push(username);
+        }
+    }
+
+    usernames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_mentions;
+
+    #[test]
+    fn parses_mentions() {
+        assert_eq!(
+            parse_mentions("@Kulla could you and @inyono.test have a look? Thanks @Kulla."),
+            vec!["Kulla", "inyono.test"]
+        );
+    }
+
+    #[test]
+    fn ignores_email_addresses() {
+        assert!(parse_mentions("Write to info@serlo.org or @@admin").is_empty());
+    }
 }
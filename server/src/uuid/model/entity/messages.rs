@@ -2,7 +2,7 @@ use actix_web::HttpResponse;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
-use super::Entity;
+use super::{Entity, UNREVISED_ENTITIES_MAX_FIRST};
 use crate::instance::Instance;
 use crate::message::MessageResponder;
 use crate::operation::{self, Operation, SuccessOutput};
@@ -20,7 +20,10 @@ pub enum EntityMessage {
     UnrevisedEntitiesQuery(unrevised_entities_query::Payload),
     DeletedEntitiesQuery(deleted_entities_query::Payload),
     EntitySetLicenseMutation(entity_set_license_mutation::Payload),
+    EntityBulkSetLicenseMutation(entity_bulk_set_license_mutation::Payload),
     EntitySortMutation(entity_sort_mutation::Payload),
+    EntityTreeQuery(entity_tree_query::Payload),
+    InvalidEntityRevisionsQuery(invalid_entity_revisions_query::Payload),
 }
 
 #[async_trait]
@@ -41,7 +44,14 @@ impl MessageResponder for EntityMessage {
             EntityMessage::UnrevisedEntitiesQuery(payload) => payload.handle(acquire_from).await,
             EntityMessage::DeletedEntitiesQuery(message) => message.handle(acquire_from).await,
             EntityMessage::EntitySetLicenseMutation(message) => message.handle(acquire_from).await,
+            EntityMessage::EntityBulkSetLicenseMutation(message) => {
+                message.handle(acquire_from).await
+            }
             EntityMessage::EntitySortMutation(message) => message.handle(acquire_from).await,
+            EntityMessage::EntityTreeQuery(message) => message.handle(acquire_from).await,
+            EntityMessage::InvalidEntityRevisionsQuery(message) => {
+                message.handle(acquire_from).await
+            }
         }
     }
 }
@@ -103,6 +113,7 @@ pub mod checkout_revision_mutation {
         pub revision_id: i32,
         pub user_id: i32,
         pub reason: String,
+        pub force: Option<bool>,
     }
 
     #[async_trait]
@@ -165,6 +176,7 @@ pub mod reject_revision_mutation {
         pub revision_id: i32,
         pub user_id: i32,
         pub reason: String,
+        pub force: Option<bool>,
     }
 
     #[async_trait]
@@ -183,15 +195,38 @@ pub mod reject_revision_mutation {
 
 pub mod unrevised_entities_query {
     use super::*;
+    use crate::datetime::DateTime;
+    use crate::review::ReviewClaim;
 
     #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
-    pub struct Payload {}
+    pub struct Payload {
+        pub instance: Option<Instance>,
+        pub subject_id: Option<i32>,
+        pub entity_type: Option<EntityType>,
+        pub author_id: Option<i32>,
+        pub is_new_author: Option<bool>,
+        pub first: Option<i32>,
+        pub after: Option<i32>,
+    }
 
-    #[derive(Debug, Deserialize, Serialize)]
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct UnrevisedEntity {
+        pub id: i32,
+        pub revision_count: i32,
+        pub oldest_revision_id: i32,
+        pub oldest_revision_date: DateTime,
+        pub author_ids: Vec<i32>,
+        pub claims: Vec<ReviewClaim>,
+    }
+
+    #[derive(Debug, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Output {
         pub unrevised_entity_ids: Vec<i32>,
+        pub unrevised_entities: Vec<UnrevisedEntity>,
+        pub has_next_page: bool,
     }
 
     #[async_trait]
@@ -202,9 +237,11 @@ pub mod unrevised_entities_query {
             &self,
             acquire_from: A,
         ) -> operation::Result<Self::Output> {
-            Ok(Output {
-                unrevised_entity_ids: Entity::unrevised_entities(acquire_from).await?,
-            })
+            if let Some(first) = self.first {
+                operation::assert_first_is_in_range(first, UNREVISED_ENTITIES_MAX_FIRST)?;
+            }
+
+            Entity::unrevised_entities(self, acquire_from).await
         }
     }
 }
@@ -282,6 +319,49 @@ pub mod entity_set_license_mutation {
     }
 }
 
+pub mod entity_bulk_set_license_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub taxonomy_term_id: Option<i32>,
+        pub entity_ids: Option<Vec<i32>>,
+        pub license_id: i32,
+        pub user_id: i32,
+        pub dry_run: Option<bool>,
+    }
+
+    /// The entities are updated in batches, each in its own transaction. When a batch fails,
+    /// `success` is `false` and `entityIds` lists the entities of the committed batches.
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub success: bool,
+        pub count: usize,
+        pub entity_ids: Vec<i32>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            if self.taxonomy_term_id.is_some() == self.entity_ids.is_some() {
+                return Err(operation::Error::BadRequest {
+                    reason: "either `taxonomyTermId` or `entityIds` needs to be provided"
+                        .to_string(),
+                });
+            }
+
+            Entity::bulk_set_license(self, acquire_from).await
+        }
+    }
+}
+
 pub mod entity_sort_mutation {
     use super::*;
 
@@ -305,3 +385,88 @@ pub mod entity_sort_mutation {
         }
     }
 }
+
+pub mod entity_tree_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub id: i32,
+        pub depth: Option<i32>,
+        pub include_trashed: Option<bool>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct EntityTreeNode {
+        pub id: i32,
+        #[serde(rename = "__typename")]
+        pub typename: EntityType,
+        pub trashed: bool,
+        pub current_revision_id: Option<i32>,
+        pub fields: HashMap<String, String>,
+        pub children: Vec<EntityTreeNode>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = EntityTreeNode;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            if !(0..=5).contains(&self.depth.unwrap_or(1)) {
+                return Err(operation::Error::BadRequest {
+                    reason: "parameter `depth` needs to be between 0 and 5".to_string(),
+                });
+            }
+
+            Entity::fetch_tree(self, acquire_from).await
+        }
+    }
+}
+
+pub mod invalid_entity_revisions_query {
+    use super::*;
+    use crate::operation::FieldError;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub after: Option<i32>,
+        pub revision_type: Option<EntityRevisionType>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct InvalidRevision {
+        pub id: i32,
+        pub repository_id: i32,
+        pub revision_type: EntityRevisionType,
+        pub errors: Vec<FieldError>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub revisions: Vec<InvalidRevision>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            Entity::invalid_revisions(self, acquire_from).await
+        }
+    }
+}
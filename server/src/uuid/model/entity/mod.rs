@@ -1,8 +1,10 @@
 use crate::uuid::Subject;
 use async_trait::async_trait;
 use convert_case::{Case, Casing};
+use itertools::Itertools;
 
 use serde::Serialize;
+use sqlx::Row;
 
 use std::collections::{HashMap, HashSet};
 
@@ -10,8 +12,9 @@ use abstract_entity::AbstractEntity;
 pub use entity_type::EntityType;
 
 use super::entity_revision::abstract_entity_revision::EntityRevisionPayload;
+use super::entity_revision::validation::validate_fields;
 use super::taxonomy_term::TaxonomyTerm;
-use super::{ConcreteUuid, EntityRevision, Uuid, UuidError, UuidFetcher};
+use super::{assemble_tree, ConcreteUuid, EntityRevision, TreeNode, Uuid, UuidError, UuidFetcher};
 
 use crate::event::{
     CreateEntityEventPayload, CreateEntityRevisionEventPayload, CreateSetLicenseEventPayload,
@@ -20,8 +23,11 @@ use crate::event::{
 
 use crate::{fetch_all_fields, format_alias};
 
+use crate::backlink::Backlink;
 use crate::datetime::DateTime;
 use crate::operation;
+use crate::review::ReviewClaim;
+use crate::search::SearchIndex;
 use crate::subscription::Subscription;
 use crate::uuid::abstract_entity_revision::EntityRevisionType;
 pub use messages::*;
@@ -303,6 +309,11 @@ impl Entity {
         payload: &entity_add_revision_mutation::Payload,
         acquire_from: A,
     ) -> Result<Uuid, operation::Error> {
+        let errors = validate_fields(payload.revision_type, &payload.input.fields);
+        if !errors.is_empty() {
+            return Err(operation::Error::InvalidFields { errors });
+        }
+
         let mut transaction = acquire_from.begin().await?;
 
         Self::assert_entity_exists(payload.input.entity_id, &mut *transaction).await?;
@@ -388,6 +399,7 @@ impl Entity {
                     revision_id: entity_revision.id,
                     user_id: payload.user_id,
                     reason: "".to_string(),
+                    force: None,
                 },
                 &mut *transaction,
             )
@@ -619,6 +631,15 @@ impl Entity {
                     });
                 }
 
+                ReviewClaim::assert_not_claimed_by_other(
+                    revision_id,
+                    payload.user_id,
+                    payload.force.unwrap_or(false),
+                    &mut *transaction,
+                )
+                .await?;
+                ReviewClaim::remove(revision_id, &mut *transaction).await?;
+
                 Uuid::set_state(revision_id, false, &mut *transaction).await?;
 
                 sqlx::query!(
@@ -633,6 +654,9 @@ impl Entity {
                 .execute(&mut *transaction)
                 .await?;
 
+                SearchIndex::update(repository_id, &mut *transaction).await?;
+                Backlink::update(repository_id, &mut *transaction).await?;
+
                 RevisionEventPayload::new(
                     false,
                     payload.user_id,
@@ -695,6 +719,15 @@ impl Entity {
                     });
                 }
 
+                ReviewClaim::assert_not_claimed_by_other(
+                    revision_id,
+                    payload.user_id,
+                    payload.force.unwrap_or(false),
+                    &mut *transaction,
+                )
+                .await?;
+                ReviewClaim::remove(revision_id, &mut *transaction).await?;
+
                 Uuid::set_state(revision_id, true, &mut *transaction).await?;
 
                 RevisionEventPayload::new(
@@ -724,21 +757,51 @@ impl Entity {
     }
 }
 
+/// Authors who have created fewer revisions than this are considered to be new authors.
+const NEW_AUTHOR_MAX_REVISIONS: i32 = 5;
+
+/// Highest number of entities the unrevised entities query returns at once. It is also used when
+/// the parameter `first` is missing.
+const UNREVISED_ENTITIES_MAX_FIRST: i32 = 10_000;
+
 impl Entity {
     pub async fn unrevised_entities<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &unrevised_entities_query::Payload,
         acquire_from: A,
-    ) -> Result<Vec<i32>, sqlx::Error> {
+    ) -> Result<unrevised_entities_query::Output, operation::Error> {
         let mut connection = acquire_from.acquire().await?;
-        Ok(sqlx::query!(
+        let first = payload.first.unwrap_or(UNREVISED_ENTITIES_MAX_FIRST);
+
+        let records = sqlx::query!(
             r#"
                 SELECT
-                    MIN(e.id) as entity_id,
-                    MIN(r.id) as min_revision_id
+                    e.id as entity_id,
+                    MIN(r.id) as min_revision_id,
+                    MIN(r.date) as min_revision_date,
+                    COUNT(r.id) as revision_count,
+                    JSON_ARRAYAGG(r.author_id) as author_ids,
+                    SUM(IFNULL(author_activity.edits, 0) < ?) as new_author_revisions,
+                    SUM(r.author_id = ?) as revisions_by_author
                 FROM entity_revision r
                 JOIN uuid u_r ON r.id = u_r.id
                 JOIN entity e ON e.id = r.repository_id
+                JOIN instance i ON i.id = e.instance_id
                 JOIN type ON e.type_id = type.id
                 JOIN uuid u_e ON e.id = u_e.id
+                LEFT JOIN (
+                    SELECT event_log.actor_id, COUNT(*) AS edits
+                    FROM event_log
+                    JOIN event ON event.id = event_log.event_id
+                    WHERE event.name = "entity/revision/add"
+                        AND event_log.actor_id IN (
+                            SELECT pending.author_id
+                            FROM entity_revision pending
+                            JOIN entity ON entity.id = pending.repository_id
+                            WHERE entity.current_revision_id IS NULL
+                                OR pending.id > entity.current_revision_id
+                        )
+                    GROUP BY event_log.actor_id
+                ) author_activity ON author_activity.actor_id = r.author_id
                 WHERE ( e.current_revision_id IS NULL OR r.id > e.current_revision_id )
                     AND u_r.trashed = 0
                     AND u_e.trashed = 0
@@ -747,15 +810,288 @@ impl Entity {
                         "math-puzzle", "multiple-choice-right-answer", "multiple-choice-wrong-answer",
                         "single-choice-right-answer", "single-choice-wrong-answer",
                         "text-solution", "grouped-text-exercise")
+                    AND (? IS NULL OR i.subdomain = ?)
+                    AND (? IS NULL OR type.name = ?)
+                    AND (? IS NULL OR EXISTS (
+                        SELECT 1
+                        FROM term_taxonomy_entity tte
+                        JOIN subject_mapping ON subject_mapping.term_taxonomy_id = tte.term_taxonomy_id
+                        LEFT JOIN entity_link el ON el.parent_id = tte.entity_id
+                        WHERE (tte.entity_id = e.id OR el.child_id = e.id)
+                            AND subject_mapping.subject_id = ?
+                            AND subject_mapping.excluded = 0
+                    ))
                 GROUP BY e.id
+                HAVING (? IS NULL OR min_revision_id > ?)
+                    AND (? IS NULL OR revisions_by_author > 0)
+                    AND (? IS NULL OR (new_author_revisions > 0) = ?)
                 ORDER BY min_revision_id
+                LIMIT ?
             "#,
+            NEW_AUTHOR_MAX_REVISIONS,
+            payload.author_id,
+            payload.instance,
+            payload.instance,
+            payload.entity_type,
+            payload.entity_type,
+            payload.subject_id,
+            payload.subject_id,
+            payload.after,
+            payload.after,
+            payload.author_id,
+            payload.is_new_author,
+            payload.is_new_author,
+            first + 1,
         )
         .fetch_all(&mut *connection)
-        .await?
-        .iter()
-        .map(|record| record.entity_id.unwrap() as i32)
-        .collect())
+        .await?;
+
+        let mut claims_by_repository: HashMap<i32, Vec<ReviewClaim>> = HashMap::new();
+        for claim in ReviewClaim::fetch_all_active(None, None, &mut *connection).await? {
+            claims_by_repository
+                .entry(claim.repository_id)
+                .or_default()
+                .push(claim);
+        }
+
+        let mut unrevised_entities = Vec::new();
+        let mut has_next_page = false;
+
+        for record in records {
+            if unrevised_entities.len() as i32 == first {
+                has_next_page = true;
+                break;
+            }
+
+            let (min_revision_id, min_revision_date) =
+                match (record.min_revision_id, record.min_revision_date) {
+                    (Some(id), Some(date)) => (id, date),
+                    _ => continue,
+                };
+
+            let author_ids = record
+                .author_ids
+                .as_ref()
+                .and_then(|value| value.as_array())
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(|id| id.as_i64())
+                        .map(|id| id as i32)
+                        .unique()
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let id = record.entity_id as i32;
+
+            unrevised_entities.push(unrevised_entities_query::UnrevisedEntity {
+                id,
+                revision_count: record.revision_count as i32,
+                oldest_revision_id: min_revision_id as i32,
+                oldest_revision_date: min_revision_date.into(),
+                author_ids,
+                claims: claims_by_repository.remove(&id).unwrap_or_default(),
+            });
+        }
+
+        Ok(unrevised_entities_query::Output {
+            unrevised_entity_ids: unrevised_entities.iter().map(|entity| entity.id).collect(),
+            unrevised_entities,
+            has_next_page,
+        })
+    }
+}
+
+impl Entity {
+    pub async fn fetch_tree<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &entity_tree_query::Payload,
+        acquire_from: A,
+    ) -> Result<entity_tree_query::EntityTreeNode, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let depth = payload.depth.unwrap_or(1);
+        let include_trashed = payload.include_trashed.unwrap_or(false);
+
+        let records = sqlx::query!(
+            r#"
+                WITH RECURSIVE tree AS (
+                    SELECT e.id, CAST(NULL AS SIGNED) AS parent_id, 0 AS depth, 0 AS position
+                    FROM entity e
+                    WHERE e.id = ?
+
+                    UNION ALL
+
+                    SELECT child.id, tree.id, tree.depth + 1, el.order
+                    FROM tree
+                    JOIN entity_link el ON el.parent_id = tree.id
+                    JOIN entity child ON child.id = el.child_id
+                    JOIN uuid child_uuid ON child_uuid.id = child.id
+                    WHERE tree.depth < ?
+                        AND (? OR child_uuid.trashed = 0)
+                )
+                SELECT
+                    tree.id,
+                    tree.parent_id,
+                    type.name AS entity_type,
+                    u.trashed,
+                    e.current_revision_id
+                FROM tree
+                JOIN entity e ON e.id = tree.id
+                JOIN uuid u ON u.id = e.id
+                JOIN type ON type.id = e.type_id
+                ORDER BY tree.depth, tree.position, tree.id
+            "#,
+            payload.id,
+            depth,
+            include_trashed,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let revision_ids: Vec<i32> = records
+            .iter()
+            .filter_map(|record| record.current_revision_id)
+            .map(|id| id as i32)
+            .collect();
+
+        let mut fields_by_revision: HashMap<i32, HashMap<String, String>> = HashMap::new();
+
+        if !revision_ids.is_empty() {
+            let params = format!("?{}", ", ?".repeat(revision_ids.len() - 1));
+            let query_str = format!(
+                r#"
+                    SELECT entity_revision_id, field, value
+                    FROM entity_revision_field
+                    WHERE entity_revision_id IN ( {params} )
+                        AND field != "changes"
+                "#
+            );
+            let mut query = sqlx::query(&query_str);
+            for id in revision_ids.iter() {
+                query = query.bind(id);
+            }
+            for row in query.fetch_all(&mut *connection).await? {
+                let field: String = row.get("field");
+                fields_by_revision
+                    .entry(row.get("entity_revision_id"))
+                    .or_default()
+                    .insert(field.to_case(Case::Camel), row.get("value"));
+            }
+        }
+
+        let nodes: Vec<(Option<i32>, entity_tree_query::EntityTreeNode)> = records
+            .into_iter()
+            .filter_map(|record| {
+                let typename = record.entity_type.parse::<EntityType>().ok()?;
+                let current_revision_id = record.current_revision_id.map(|id| id as i32);
+
+                Some((
+                    record.parent_id.map(|id| id as i32),
+                    entity_tree_query::EntityTreeNode {
+                        id: record.id as i32,
+                        typename,
+                        trashed: record.trashed != 0,
+                        current_revision_id,
+                        fields: current_revision_id
+                            .and_then(|id| fields_by_revision.remove(&id))
+                            .unwrap_or_default(),
+                        children: Vec::new(),
+                    },
+                ))
+            })
+            .collect();
+
+        assemble_tree(nodes).ok_or(operation::Error::NotFoundError)
+    }
+}
+
+impl TreeNode for entity_tree_query::EntityTreeNode {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Self> {
+        &mut self.children
+    }
+}
+
+impl Entity {
+    pub async fn invalid_revisions<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &invalid_entity_revisions_query::Payload,
+        acquire_from: A,
+    ) -> Result<invalid_entity_revisions_query::Output, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let entity_type = payload.revision_type.map(EntityType::from);
+
+        let records = sqlx::query!(
+            r#"
+                SELECT
+                    r.id,
+                    r.repository_id,
+                    type.name AS entity_type,
+                    JSON_REMOVE(
+                        JSON_OBJECTAGG(IFNULL(f.field, "__unused_key"), f.value),
+                        "$.__unused_key"
+                    ) AS fields
+                FROM entity_revision r
+                JOIN entity e ON e.id = r.repository_id
+                JOIN type ON type.id = e.type_id
+                LEFT JOIN entity_revision_field f
+                    ON f.entity_revision_id = r.id AND f.field != "changes"
+                WHERE (? IS NULL OR r.id > ?)
+                    AND type.name NOT IN ("input-expression-equal-match-challenge",
+                        "input-number-exact-match-challenge", "input-string-normalized-match-challenge",
+                        "math-puzzle", "multiple-choice-right-answer", "multiple-choice-wrong-answer",
+                        "single-choice-right-answer", "single-choice-wrong-answer",
+                        "text-solution", "grouped-text-exercise")
+                    AND (? IS NULL OR type.name = ?)
+                GROUP BY r.id
+                ORDER BY r.id
+                LIMIT ?
+            "#,
+            payload.after,
+            payload.after,
+            entity_type,
+            entity_type,
+            payload.first + 1,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let has_next_page = records.len() as i32 > payload.first;
+        let mut revisions = Vec::new();
+
+        for record in records.into_iter().take(payload.first as usize) {
+            let revision_type: EntityRevisionType = record.entity_type.parse()?;
+            let fields: HashMap<String, String> = record
+                .fields
+                .as_ref()
+                .and_then(|value| value.as_object())
+                .map(|object| {
+                    object
+                        .iter()
+                        .filter_map(|(key, value)| {
+                            value.as_str().map(|value| (key.clone(), value.to_string()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let errors = validate_fields(revision_type, &fields);
+
+            if !errors.is_empty() {
+                revisions.push(invalid_entity_revisions_query::InvalidRevision {
+                    id: record.id as i32,
+                    repository_id: record.repository_id as i32,
+                    revision_type,
+                    errors,
+                });
+            }
+        }
+
+        Ok(invalid_entity_revisions_query::Output {
+            revisions,
+            has_next_page,
+        })
     }
 }
 
@@ -910,6 +1246,147 @@ impl Entity {
     }
 }
 
+impl Entity {
+    pub async fn bulk_set_license<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &entity_bulk_set_license_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<entity_bulk_set_license_mutation::Output, operation::Error> {
+        const BATCH_SIZE: usize = 100;
+
+        let mut connection = acquire_from.acquire().await?;
+
+        sqlx::query!(r#"SELECT id FROM user WHERE id = ?"#, payload.user_id)
+            .fetch_optional(&mut *connection)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: format!("An user with id {} does not exist.", payload.user_id),
+            })?;
+
+        sqlx::query!(r#"SELECT id FROM license WHERE id = ?"#, payload.license_id)
+            .fetch_optional(&mut *connection)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: format!("A license with id {} does not exist.", payload.license_id),
+            })?;
+
+        let entities: Vec<(i32, i32)> = match (&payload.taxonomy_term_id, &payload.entity_ids) {
+            (Some(taxonomy_term_id), _) => sqlx::query!(
+                r#"
+                    WITH RECURSIVE subtree AS (
+                        SELECT id FROM term_taxonomy WHERE id = ?
+                        UNION
+                        SELECT child.id
+                        FROM term_taxonomy child
+                        JOIN subtree ON child.parent_id = subtree.id
+                    ),
+                    linked_entity AS (
+                        SELECT tte.entity_id AS id
+                        FROM term_taxonomy_entity tte
+                        JOIN subtree ON subtree.id = tte.term_taxonomy_id
+                    )
+                    SELECT DISTINCT e.id, e.instance_id
+                    FROM entity e
+                    JOIN uuid u ON u.id = e.id
+                    LEFT JOIN entity_link el ON el.child_id = e.id
+                    WHERE (e.id IN (SELECT id FROM linked_entity)
+                            OR el.parent_id IN (SELECT id FROM linked_entity))
+                        AND u.trashed = 0
+                        AND e.license_id != ?
+                    ORDER BY e.id
+                "#,
+                taxonomy_term_id,
+                payload.license_id,
+            )
+            .fetch_all(&mut *connection)
+            .await?
+            .into_iter()
+            .map(|record| (record.id as i32, record.instance_id as i32))
+            .collect(),
+            (None, Some(entity_ids)) if !entity_ids.is_empty() => {
+                let params = format!("?{}", ", ?".repeat(entity_ids.len() - 1));
+                let query_str = format!(
+                    r#"
+                        SELECT e.id, e.instance_id
+                        FROM entity e
+                        JOIN uuid u ON u.id = e.id
+                        WHERE e.id IN ( {params} )
+                            AND u.trashed = 0
+                            AND e.license_id != ?
+                        ORDER BY e.id
+                    "#
+                );
+                let mut query = sqlx::query(&query_str);
+                for id in entity_ids.iter() {
+                    query = query.bind(id);
+                }
+                query
+                    .bind(payload.license_id)
+                    .fetch_all(&mut *connection)
+                    .await?
+                    .into_iter()
+                    .map(|row| (row.get("id"), row.get("instance_id")))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        if payload.dry_run.unwrap_or(false) {
+            return Ok(entity_bulk_set_license_mutation::Output {
+                success: true,
+                count: entities.len(),
+                entity_ids: entities.into_iter().map(|(id, _)| id).collect(),
+            });
+        }
+
+        let mut applied_entity_ids: Vec<i32> = Vec::new();
+
+        for batch in entities.chunks(BATCH_SIZE) {
+            // Earlier batches are already committed, so a failing batch must not hide them
+            if let Err(error) = Self::set_license_of_batch(batch, payload, &mut *connection).await {
+                println!("{:?}: {error}", payload);
+                return Ok(entity_bulk_set_license_mutation::Output {
+                    success: false,
+                    count: applied_entity_ids.len(),
+                    entity_ids: applied_entity_ids,
+                });
+            }
+
+            applied_entity_ids.extend(batch.iter().map(|(id, _)| id));
+        }
+
+        Ok(entity_bulk_set_license_mutation::Output {
+            success: true,
+            count: applied_entity_ids.len(),
+            entity_ids: applied_entity_ids,
+        })
+    }
+
+    async fn set_license_of_batch<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        batch: &[(i32, i32)],
+        payload: &entity_bulk_set_license_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        for (entity_id, instance_id) in batch {
+            sqlx::query!(
+                r#"UPDATE entity SET license_id = ? WHERE id = ?"#,
+                payload.license_id,
+                entity_id,
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            CreateSetLicenseEventPayload::new(*entity_id, payload.user_id, *instance_id)
+                .save(&mut *transaction)
+                .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Duration;
@@ -948,7 +1425,10 @@ mod tests {
                     subscribe_this: false,
                     subscribe_this_by_email: false,
                     fields: HashMap::from([
-                        ("content".to_string(), "test content".to_string()),
+                        (
+                            "content".to_string(),
+                            r#"{"plugin":"rows","state":[]}"#.to_string(),
+                        ),
                         (
                             "meta_description".to_string(),
                             "test meta-description".to_string(),
@@ -991,7 +1471,10 @@ mod tests {
                     subscribe_this: false,
                     subscribe_this_by_email: false,
                     fields: HashMap::from([
-                        ("content".to_string(), "test content".to_string()),
+                        (
+                            "content".to_string(),
+                            r#"{"plugin":"rows","state":[]}"#.to_string(),
+                        ),
                         (
                             "meta_description".to_string(),
                             "test meta-description".to_string(),
@@ -1033,7 +1516,10 @@ mod tests {
                     subscribe_this: true,
                     subscribe_this_by_email: true,
                     fields: HashMap::from([
-                        ("content".to_string(), "test content".to_string()),
+                        (
+                            "content".to_string(),
+                            r#"{"plugin":"rows","state":[]}"#.to_string(),
+                        ),
                         (
                             "meta_description".to_string(),
                             "test meta-description".to_string(),
@@ -1074,6 +1560,7 @@ mod tests {
                 revision_id: 30672,
                 user_id: 1,
                 reason: "Revert changes".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -1124,6 +1611,7 @@ mod tests {
                 revision_id: 30674,
                 user_id: 1,
                 reason: "Revert changes".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -1149,6 +1637,7 @@ mod tests {
                 revision_id: 30672,
                 user_id: 1,
                 reason: "Contains an error".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -1182,6 +1671,7 @@ mod tests {
                 revision_id: 30672,
                 user_id: 1,
                 reason: "Contains an error".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -1207,6 +1697,7 @@ mod tests {
                 revision_id: 30674,
                 user_id: 1,
                 reason: "Contains an error".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -58,6 +58,21 @@ impl From<EntityType> for EntityRevisionType {
     }
 }
 
+impl From<EntityRevisionType> for EntityType {
+    fn from(revision_type: EntityRevisionType) -> Self {
+        match revision_type {
+            EntityRevisionType::Applet => Self::Applet,
+            EntityRevisionType::Article => Self::Article,
+            EntityRevisionType::Course => Self::Course,
+            EntityRevisionType::CoursePage => Self::CoursePage,
+            EntityRevisionType::Event => Self::Event,
+            EntityRevisionType::Exercise => Self::Exercise,
+            EntityRevisionType::ExerciseGroup => Self::ExerciseGroup,
+            EntityRevisionType::Video => Self::Video,
+        }
+    }
+}
+
 impl std::str::FromStr for EntityRevisionType {
     type Err = UuidError;
 
@@ -24,6 +24,7 @@ mod course_revision;
 mod event_revision;
 mod exercise_group_revision;
 mod generic_entity_revision;
+pub mod validation;
 mod video_revision;
 
 #[derive(Debug, Serialize)]
@@ -0,0 +1,167 @@
+use convert_case::{Case, Casing};
+use std::collections::HashMap;
+
+use super::abstract_entity_revision::EntityRevisionType;
+use crate::operation::FieldError;
+
+impl EntityRevisionType {
+    pub fn allowed_fields(&self) -> &'static [&'static str] {
+        match self {
+            Self::Applet => &["content", "title", "metaTitle", "metaDescription", "url"],
+            Self::Article => &["content", "title", "metaTitle", "metaDescription"],
+            Self::Course => &["content", "description", "title", "metaDescription"],
+            Self::CoursePage => &["content", "title", "icon"],
+            Self::Event => &["content", "title", "metaTitle", "metaDescription"],
+            Self::Exercise => &["content"],
+            Self::ExerciseGroup => &["content", "cohesive"],
+            // For videos `content` holds the url and `description` the editor state
+            Self::Video => &["content", "title", "description"],
+        }
+    }
+
+    pub fn required_fields(&self) -> &'static [&'static str] {
+        match self {
+            Self::Applet => &["content", "title", "url"],
+            Self::Article | Self::CoursePage | Self::Event | Self::Video => &["content", "title"],
+            Self::Course => &["title"],
+            Self::Exercise | Self::ExerciseGroup => &["content"],
+        }
+    }
+
+    pub fn editor_state_fields(&self) -> &'static [&'static str] {
+        match self {
+            Self::Course => &["content", "description"],
+            Self::Video => &["description"],
+            _ => &["content"],
+        }
+    }
+}
+
+/// Checks the fields of an entity revision against the schema of its revision type. Field names
+/// may be given in camel case (like in mutations) or in snake case (like in the database).
+pub fn validate_fields(
+    revision_type: EntityRevisionType,
+    fields: &HashMap<String, String>,
+) -> Vec<FieldError> {
+    let fields: HashMap<String, &String> = fields
+        .iter()
+        .map(|(name, value)| (name.to_case(Case::Camel), value))
+        .collect();
+    let mut errors = Vec::new();
+
+    for name in fields.keys() {
+        if !revision_type.allowed_fields().contains(&name.as_str()) {
+            errors.push(FieldError {
+                field: name.clone(),
+                reason: "field is not allowed for this revision type".to_string(),
+            });
+        }
+    }
+
+    for name in revision_type.required_fields() {
+        if fields.get(*name).map_or(true, |value| value.is_empty()) {
+            errors.push(FieldError {
+                field: name.to_string(),
+                reason: "field is required".to_string(),
+            });
+        }
+    }
+
+    for name in revision_type.editor_state_fields() {
+        if let Some(value) = fields.get(*name).filter(|value| !value.is_empty()) {
+            if !is_editor_state(value) {
+                errors.push(FieldError {
+                    field: name.to_string(),
+                    reason: "field is not valid editor state".to_string(),
+                });
+            }
+        }
+    }
+
+    if let Some(cohesive) = fields.get("cohesive") {
+        if cohesive.as_str() != "true" && cohesive.as_str() != "false" {
+            errors.push(FieldError {
+                field: "cohesive".to_string(),
+                reason: "field needs to be `true` or `false`".to_string(),
+            });
+        }
+    }
+
+    errors.sort_by(|a, b| a.field.cmp(&b.field));
+    errors
+}
+
+/// An editor state is a JSON object like `{"plugin":"rows","state":[...]}`.
+fn is_editor_state(value: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(value)
+        .ok()
+        .and_then(|document| document.get("plugin").map(|plugin| plugin.is_string()))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::validate_fields;
+    use crate::uuid::abstract_entity_revision::EntityRevisionType;
+
+    fn fields(fields: &[(&str, &str)]) -> HashMap<String, String> {
+        fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_article_fields() {
+        let errors = validate_fields(
+            EntityRevisionType::Article,
+            &fields(&[
+                ("content", r#"{"plugin":"article","state":{}}"#),
+                ("title", "title"),
+                ("meta_title", "meta title"),
+                ("metaDescription", "meta description"),
+            ]),
+        );
+
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn reports_unknown_missing_and_malformed_fields() {
+        let errors = validate_fields(
+            EntityRevisionType::Article,
+            &fields(&[("content", "no json"), ("foo", "bar")]),
+        );
+
+        let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(fields, vec!["content", "foo", "title"]);
+    }
+
+    #[test]
+    fn validates_description_of_videos_as_editor_state() {
+        let errors = validate_fields(
+            EntityRevisionType::Video,
+            &fields(&[
+                ("content", "https://www.youtube.com/watch?v=a"),
+                ("title", "title"),
+                ("description", "[]"),
+            ]),
+        );
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "description");
+    }
+
+    #[test]
+    fn validates_cohesive() {
+        let errors = validate_fields(
+            EntityRevisionType::ExerciseGroup,
+            &fields(&[("content", r#"{"plugin":"rows"}"#), ("cohesive", "yes")]),
+        );
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "cohesive");
+    }
+}
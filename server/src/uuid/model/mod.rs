@@ -4,6 +4,7 @@ pub use entity_revision::*;
 pub use page::PageMessage;
 pub use page_revision::*;
 pub use taxonomy_term::*;
+pub use tree::*;
 pub use user::*;
 pub use uuid::*;
 
@@ -11,8 +12,9 @@ mod comment;
 mod discriminator;
 mod entity;
 mod entity_revision;
-mod page;
+pub mod page;
 mod page_revision;
 mod taxonomy_term;
+mod tree;
 mod user;
 mod uuid;
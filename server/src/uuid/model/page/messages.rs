@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::instance::Instance;
 use crate::message::MessageResponder;
 use crate::operation::{self, Operation};
+use crate::review::ReviewClaim;
 use crate::uuid::Uuid;
 
 use super::{Page, PageCheckoutRevisionError, PageRejectRevisionError};
@@ -16,6 +17,7 @@ pub enum PageMessage {
     PageCheckoutRevisionMutation(checkout_revision_mutation::Payload),
     PageCreateMutation(create_mutation::Payload),
     PageRejectRevisionMutation(reject_revision_mutation::Payload),
+    PageSetMetadataMutation(set_metadata_mutation::Payload),
     PagesQuery(pages_query::Payload),
 }
 
@@ -32,6 +34,7 @@ impl MessageResponder for PageMessage {
             }
             PageMessage::PageCreateMutation(payload) => payload.handle(acquire_from).await,
             PageMessage::PageRejectRevisionMutation(payload) => payload.handle(acquire_from).await,
+            PageMessage::PageSetMetadataMutation(payload) => payload.handle(acquire_from).await,
             PageMessage::PagesQuery(payload) => payload.handle(acquire_from).await,
         }
     }
@@ -82,6 +85,7 @@ pub mod checkout_revision_mutation {
         pub revision_id: i32,
         pub user_id: i32,
         pub reason: String,
+        pub force: Option<bool>,
     }
 
     #[async_trait]
@@ -92,7 +96,19 @@ pub mod checkout_revision_mutation {
             &self,
             acquire_from: A,
         ) -> operation::Result<Self::Output> {
-            Page::checkout_revision(self, acquire_from).await?;
+            let mut transaction = acquire_from.begin().await?;
+
+            ReviewClaim::assert_not_claimed_by_other(
+                self.revision_id,
+                self.user_id,
+                self.force.unwrap_or(false),
+                &mut *transaction,
+            )
+            .await?;
+            Page::checkout_revision(self, &mut *transaction).await?;
+
+            transaction.commit().await?;
+
             Ok(operation::SuccessOutput { success: true })
         }
     }
@@ -159,6 +175,7 @@ pub mod reject_revision_mutation {
         pub revision_id: i32,
         pub user_id: i32,
         pub reason: String,
+        pub force: Option<bool>,
     }
 
     #[async_trait]
@@ -169,7 +186,19 @@ pub mod reject_revision_mutation {
             &self,
             acquire_from: A,
         ) -> operation::Result<Self::Output> {
-            Page::reject_revision(self, acquire_from).await?;
+            let mut transaction = acquire_from.begin().await?;
+
+            ReviewClaim::assert_not_claimed_by_other(
+                self.revision_id,
+                self.user_id,
+                self.force.unwrap_or(false),
+                &mut *transaction,
+            )
+            .await?;
+            Page::reject_revision(self, &mut *transaction).await?;
+
+            transaction.commit().await?;
+
             Ok(operation::SuccessOutput { success: true })
         }
     }
@@ -200,6 +229,34 @@ pub mod reject_revision_mutation {
     }
 }
 
+pub mod set_metadata_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub page_id: i32,
+        pub user_id: i32,
+        pub discussions_enabled: bool,
+        pub forum_id: Option<i32>,
+        pub license_id: i32,
+        pub instance: Instance,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = operation::SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            Page::set_metadata(self, acquire_from).await?;
+            Ok(operation::SuccessOutput { success: true })
+        }
+    }
+}
+
 pub mod pages_query {
     use super::*;
 
@@ -207,12 +264,26 @@ pub mod pages_query {
     #[serde(rename_all = "camelCase")]
     pub struct Payload {
         pub instance: Option<Instance>,
+        pub trashed: Option<bool>,
+        pub first: Option<i32>,
+        pub after: Option<i32>,
     }
 
     #[derive(Debug, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Output {
         pub pages: Vec<i32>,
+        pub nodes: Vec<PageNode>,
+        pub has_next_page: bool,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct PageNode {
+        pub id: i32,
+        pub title: String,
+        pub alias: String,
+        pub trashed: bool,
     }
 
     #[async_trait]
@@ -223,8 +294,11 @@ pub mod pages_query {
             &self,
             acquire_from: A,
         ) -> operation::Result<Self::Output> {
-            let result = Page::fetch_all_pages(self, acquire_from).await?;
-            Ok(Output { pages: result })
+            if let Some(first) = self.first {
+                operation::assert_first_is_in_range(first, 10_000)?;
+            }
+
+            Ok(Page::fetch_all_pages(self, acquire_from).await?)
         }
     }
 }
@@ -4,13 +4,19 @@ use serde::Serialize;
 
 use thiserror::Error;
 
-use super::{ConcreteUuid, Uuid, UuidError, UuidFetcher};
+use super::{ConcreteUuid, TaxonomyTerm, TaxonomyType, Uuid, UuidError, UuidFetcher};
 use crate::datetime::DateTime;
 use crate::format_alias;
 use crate::instance::Instance;
 
-use crate::event::{CreateEntityRevisionEventPayload, EventError, RevisionEventPayload};
+use crate::backlink::Backlink;
+use crate::event::{
+    CreateEntityRevisionEventPayload, CreateSetLicenseEventPayload, EventError, PageDiscussions,
+    RevisionEventPayload, SetPageMetadataEventPayload,
+};
 use crate::operation;
+use crate::review::ReviewClaim;
+use crate::search::SearchIndex;
 use crate::uuid::PageRevision;
 pub use messages::*;
 
@@ -180,6 +186,7 @@ impl Page {
                 revision_id: page_revision_id,
                 user_id: payload.user_id,
                 reason: "".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -256,6 +263,8 @@ impl Page {
                     return Err(PageCheckoutRevisionError::RevisionAlreadyCheckedOut);
                 }
 
+                ReviewClaim::remove(revision_id, &mut *transaction).await?;
+
                 Uuid::set_state(revision_id, false, &mut *transaction).await?;
 
                 sqlx::query!(
@@ -270,6 +279,9 @@ impl Page {
                 .execute(&mut *transaction)
                 .await?;
 
+                SearchIndex::update(repository_id, &mut *transaction).await?;
+                Backlink::update(repository_id, &mut *transaction).await?;
+
                 RevisionEventPayload::new(
                     false,
                     payload.user_id,
@@ -430,6 +442,8 @@ impl Page {
                     return Err(PageRejectRevisionError::RevisionCurrentlyCheckedOut);
                 }
 
+                ReviewClaim::remove(revision_id, &mut *transaction).await?;
+
                 Uuid::set_state(revision_id, true, &mut *transaction).await?;
 
                 RevisionEventPayload::new(
@@ -456,29 +470,160 @@ impl Page {
 }
 
 impl Page {
+    pub async fn set_metadata<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &set_metadata_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        sqlx::query!(r#"SELECT id FROM user WHERE id = ?"#, payload.user_id)
+            .fetch_optional(&mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: format!("An user with id {} does not exist.", payload.user_id),
+            })?;
+
+        let page = sqlx::query!(
+            r#"
+                SELECT p.instance_id, instance.subdomain, p.license_id, p.discussions_enabled,
+                        p.forum_id
+                    FROM page_repository p
+                    JOIN instance ON instance.id = p.instance_id
+                    WHERE p.id = ?
+            "#,
+            payload.page_id
+        )
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(operation::Error::BadRequest {
+            reason: format!("A page with id {} does not exist.", payload.page_id),
+        })?;
+
+        sqlx::query!(r#"SELECT id FROM license WHERE id = ?"#, payload.license_id)
+            .fetch_optional(&mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: format!("A license with id {} does not exist.", payload.license_id),
+            })?;
+
+        if let Some(forum_id) = payload.forum_id {
+            if TaxonomyTerm::fetch_type(forum_id, &mut *transaction).await?
+                != Some(TaxonomyType::Forum)
+            {
+                return Err(operation::Error::BadRequest {
+                    reason: format!("Taxonomy term with id {forum_id} is not a forum"),
+                });
+            }
+        }
+
+        let instance_id = Instance::fetch_id(&payload.instance, &mut *transaction).await?;
+
+        sqlx::query!(
+            r#"
+                UPDATE page_repository
+                    SET instance_id = ?, license_id = ?, discussions_enabled = ?, forum_id = ?
+                    WHERE id = ?
+            "#,
+            instance_id,
+            payload.license_id,
+            payload.discussions_enabled,
+            payload.forum_id,
+            payload.page_id,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        if page.license_id != payload.license_id {
+            CreateSetLicenseEventPayload::new(payload.page_id, payload.user_id, instance_id)
+                .save(&mut *transaction)
+                .await?;
+        }
+
+        let previous_discussions = PageDiscussions {
+            enabled: page.discussions_enabled != 0,
+            forum_id: page.forum_id.map(|id| id as i32),
+        };
+
+        if page.instance_id as i32 != instance_id
+            || previous_discussions.enabled != payload.discussions_enabled
+            || previous_discussions.forum_id != payload.forum_id
+        {
+            SetPageMetadataEventPayload::new(
+                payload.page_id,
+                payload.user_id,
+                instance_id,
+                page.subdomain,
+                previous_discussions,
+                PageDiscussions {
+                    enabled: payload.discussions_enabled,
+                    forum_id: payload.forum_id,
+                },
+            )
+            .save(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
     pub async fn fetch_all_pages<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
         payload: &pages_query::Payload,
         acquire_from: A,
-    ) -> Result<Vec<i32>, sqlx::Error> {
+    ) -> Result<pages_query::Output, sqlx::Error> {
         let mut connection = acquire_from.acquire().await?;
-        Ok(sqlx::query!(
+        let limit = payload.first.map(|first| first + 1);
+
+        let mut nodes: Vec<pages_query::PageNode> = sqlx::query!(
             r#"
-                SELECT page_repository.id
+                SELECT page_repository.id, page_revision.title, uuid.trashed
                 FROM instance, page_repository
                 JOIN page_revision ON page_repository.id = page_revision.page_repository_id
+                JOIN uuid ON uuid.id = page_repository.id
                 WHERE page_repository.instance_id = instance.id
                 AND (? is null or instance.subdomain = ?)
                 AND page_repository.current_revision_id = page_revision.id
+                AND (? is null or uuid.trashed = ?)
+                AND (? is null or page_repository.id > ?)
                 ORDER BY page_repository.id
+                LIMIT ?
             "#,
             payload.instance,
             payload.instance,
+            payload.trashed,
+            payload.trashed,
+            payload.after,
+            payload.after,
+            limit.unwrap_or(i32::MAX),
         )
         .fetch_all(&mut *connection)
         .await?
         .into_iter()
-        .map(|result| result.id as i32)
-        .collect())
+        .map(|result| {
+            let id = result.id as i32;
+
+            pages_query::PageNode {
+                id,
+                alias: format_alias(None, id, Some(&result.title)),
+                title: result.title,
+                trashed: result.trashed != 0,
+            }
+        })
+        .collect();
+
+        let has_next_page = payload
+            .first
+            .is_some_and(|first| nodes.len() as i32 > first);
+        if let Some(first) = payload.first {
+            nodes.truncate(first as usize);
+        }
+
+        Ok(pages_query::Output {
+            pages: nodes.iter().map(|node| node.id).collect(),
+            nodes,
+            has_next_page,
+        })
     }
 }
 
@@ -552,6 +697,7 @@ mod tests {
                 revision_id: 33220,
                 user_id: 1,
                 reason: "Revert changes".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -599,6 +745,7 @@ mod tests {
                 revision_id: 35476,
                 user_id: 1,
                 reason: "Revert changes".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -624,6 +771,7 @@ mod tests {
                 revision_id: 33220,
                 user_id: 1,
                 reason: "Contains an error".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -655,6 +803,7 @@ mod tests {
                 revision_id: 33220,
                 user_id: 1,
                 reason: "Contains an error".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
@@ -680,6 +829,7 @@ mod tests {
                 revision_id: 35476,
                 user_id: 1,
                 reason: "Contains an error".to_string(),
+                force: None,
             },
             &mut *transaction,
         )
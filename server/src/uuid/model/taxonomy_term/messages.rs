@@ -1,8 +1,10 @@
 use actix_web::HttpResponse;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::TaxonomyTerm;
+use crate::instance::Instance;
 use crate::message::MessageResponder;
 use crate::operation::{self, Operation, SuccessOutput};
 use crate::uuid::{TaxonomyType, Uuid};
@@ -17,6 +19,10 @@ pub enum TaxonomyTermMessage {
     TaxonomyCreateEntityLinksMutation(taxonomy_create_entity_links_mutation::Payload),
     TaxonomyDeleteEntityLinksMutation(taxonomy_delete_entity_links_mutation::Payload),
     TaxonomySortMutation(taxonomy_sort_mutation::Payload),
+    TaxonomyTermMoveMutation(taxonomy_term_move_mutation::Payload),
+    InvalidTaxonomyTermsQuery(invalid_taxonomy_terms_query::Payload),
+    TaxonomyTreeQuery(taxonomy_tree_query::Payload),
+    TaxonomyTermMergeMutation(taxonomy_term_merge_mutation::Payload),
 }
 
 #[async_trait]
@@ -41,6 +47,16 @@ impl MessageResponder for TaxonomyTermMessage {
             TaxonomyTermMessage::TaxonomySortMutation(payload) => {
                 payload.handle(acquire_from).await
             }
+            TaxonomyTermMessage::TaxonomyTermMoveMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
+            TaxonomyTermMessage::InvalidTaxonomyTermsQuery(payload) => {
+                payload.handle(acquire_from).await
+            }
+            TaxonomyTermMessage::TaxonomyTreeQuery(payload) => payload.handle(acquire_from).await,
+            TaxonomyTermMessage::TaxonomyTermMergeMutation(payload) => {
+                payload.handle(acquire_from).await
+            }
         }
     }
 }
@@ -177,3 +193,141 @@ pub mod taxonomy_sort_mutation {
         }
     }
 }
+
+pub mod taxonomy_term_move_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub ids: Vec<i32>,
+        pub destination: i32,
+        pub user_id: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            TaxonomyTerm::move_terms(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
+
+pub mod invalid_taxonomy_terms_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub first: i32,
+        pub after: Option<i32>,
+        pub instance: Option<Instance>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct InvalidTaxonomyTerm {
+        pub id: i32,
+        #[serde(rename = "type")]
+        pub term_type: String,
+        pub parent_id: Option<i32>,
+        pub parent_type: Option<String>,
+        pub reason: String,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Output {
+        pub terms: Vec<InvalidTaxonomyTerm>,
+        pub has_next_page: bool,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = Output;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            operation::assert_first_is_in_range(self.first, 10_000)?;
+
+            TaxonomyTerm::invalid_terms(self, acquire_from).await
+        }
+    }
+}
+
+pub mod taxonomy_tree_query {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub root_id: i32,
+        pub max_depth: Option<i32>,
+        pub include_trashed: Option<bool>,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TaxonomyTreeNode {
+        pub id: i32,
+        pub name: String,
+        #[serde(rename = "type")]
+        pub term_type: String,
+        pub weight: i32,
+        pub trashed: bool,
+        pub direct_entity_counts: HashMap<String, i32>,
+        pub transitive_entity_counts: HashMap<String, i32>,
+        pub children: Vec<TaxonomyTreeNode>,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = TaxonomyTreeNode;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            if self.max_depth.map_or(false, |max_depth| max_depth < 0) {
+                return Err(operation::Error::BadRequest {
+                    reason: "parameter `maxDepth` must not be negative".to_string(),
+                });
+            }
+
+            TaxonomyTerm::fetch_tree(self, acquire_from).await
+        }
+    }
+}
+
+pub mod taxonomy_term_merge_mutation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Payload {
+        pub source_id: i32,
+        pub target_id: i32,
+        pub user_id: i32,
+    }
+
+    #[async_trait]
+    impl Operation for Payload {
+        type Output = SuccessOutput;
+
+        async fn execute<'e, A: sqlx::Acquire<'e, Database = sqlx::MySql> + std::marker::Send>(
+            &self,
+            acquire_from: A,
+        ) -> operation::Result<Self::Output> {
+            TaxonomyTerm::merge(self, acquire_from).await?;
+            Ok(SuccessOutput { success: true })
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
 use convert_case::{Case, Casing};
@@ -9,11 +9,11 @@ use sqlx::encode::IsNull;
 use sqlx::mysql::MySqlTypeInfo;
 use sqlx::MySql;
 
-use super::{AssertExists, ConcreteUuid, Uuid, UuidError, UuidFetcher};
+use super::{assemble_tree, AssertExists, ConcreteUuid, TreeNode, Uuid, UuidError, UuidFetcher};
 
 use crate::event::{
     CreateTaxonomyLinkEventPayload, CreateTaxonomyTermEventPayload, RemoveTaxonomyLinkEventPayload,
-    SetTaxonomyTermEventPayload,
+    SetTaxonomyParentEventPayload, SetTaxonomyTermEventPayload, SetUuidStateEventPayload,
 };
 use crate::instance::Instance;
 use crate::uuid::Entity;
@@ -44,6 +44,79 @@ pub enum TaxonomyType {
     TopicFolder, // below Topic
 }
 
+impl TaxonomyType {
+    pub const ALL: [TaxonomyType; 11] = [
+        Self::Root,
+        Self::Blog,
+        Self::ForumCategory,
+        Self::Forum,
+        Self::Subject,
+        Self::Locale,
+        Self::Curriculum,
+        Self::CurriculumTopic,
+        Self::CurriculumTopicFolder,
+        Self::Topic,
+        Self::TopicFolder,
+    ];
+
+    /// The types a term of this type may be placed below, see the comments above.
+    pub fn allowed_parent_types(&self) -> &'static [TaxonomyType] {
+        match self {
+            Self::Root => &[],
+            Self::Blog | Self::Subject => &[Self::Root],
+            Self::ForumCategory => &[Self::Root, Self::ForumCategory],
+            Self::Forum => &[Self::ForumCategory],
+            Self::Locale => &[Self::Subject, Self::Locale],
+            Self::Curriculum => &[Self::Locale],
+            Self::CurriculumTopic => &[Self::Curriculum, Self::CurriculumTopic],
+            Self::CurriculumTopicFolder => &[Self::CurriculumTopic],
+            Self::Topic => &[Self::Subject, Self::Topic],
+            Self::TopicFolder => &[Self::Topic],
+        }
+    }
+
+    pub fn allows_parent(&self, parent_type: &TaxonomyType) -> bool {
+        self.allowed_parent_types().contains(parent_type)
+    }
+
+    /// Exercises belong into folders, all other entities (except course pages which belong to
+    /// their course) may be linked to every term which is no folder.
+    pub fn allows_entity_type(&self, entity_type: &EntityType) -> bool {
+        match self {
+            Self::TopicFolder | Self::CurriculumTopicFolder => {
+                matches!(
+                    entity_type,
+                    EntityType::Exercise | EntityType::ExerciseGroup
+                )
+            }
+            _ => !matches!(
+                entity_type,
+                EntityType::Exercise | EntityType::ExerciseGroup | EntityType::CoursePage
+            ),
+        }
+    }
+
+    /// All allowed combinations of child and parent type as strings like `topic/subject`.
+    pub fn allowed_type_pairs() -> Vec<String> {
+        Self::ALL
+            .iter()
+            .flat_map(|child_type| {
+                child_type
+                    .allowed_parent_types()
+                    .iter()
+                    .map(move |parent_type| format!("{}/{}", child_type.name(), parent_type.name()))
+            })
+            .collect()
+    }
+
+    pub fn name(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|name| name.as_str().map(String::from))
+            .unwrap_or_default()
+    }
+}
+
 impl std::str::FromStr for TaxonomyType {
     type Err = serde_json::Error;
 
@@ -200,6 +273,12 @@ impl UuidFetcher for TaxonomyTerm {
     }
 }
 
+struct TermTypeAndPosition {
+    term_type: TaxonomyType,
+    instance_id: i32,
+    parent_id: Option<i32>,
+}
+
 pub struct Subject {
     pub taxonomy_term_id: i32,
     pub name: String,
@@ -238,9 +317,20 @@ impl TaxonomyTerm {
                     JOIN term t on t1.term_id = t.id
                     WHERE
                         (
-                            t0.id = 146728 OR
-                            t0.id = 106081 OR
-                            (t0.parent_id IS NULL AND t2.id != 146728 AND t1.id != 106081)
+                            t0.id IN (
+                                SELECT term_taxonomy_id FROM subject_registry
+                                WHERE kind IN ("in-construction", "partner-root")
+                            ) OR (
+                                t0.parent_id IS NULL AND
+                                t2.id NOT IN (
+                                    SELECT term_taxonomy_id FROM subject_registry
+                                    WHERE kind = "partner-root"
+                                ) AND
+                                t1.id NOT IN (
+                                    SELECT term_taxonomy_id FROM subject_registry
+                                    WHERE kind = "in-construction"
+                                )
+                            )
                         ) AND (
                             t1.id = ? OR t2.id = ? OR t3.id = ? OR t4.id = ? OR t5.id = ? OR
                             t6.id = ? OR t7.id = ? OR t8.id = ? OR t9.id = ? OR t10.id = ? OR
@@ -384,6 +474,21 @@ impl TaxonomyTerm {
     ) -> Result<Uuid, operation::Error> {
         let mut transaction = acquire_from.begin().await?;
 
+        let parent = Self::fetch_type_and_position(payload.parent_id, &mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: format!("Taxonomy term with id {} does not exist", payload.parent_id),
+            })?;
+
+        if !payload.taxonomy_type.allows_parent(&parent.term_type) {
+            return Err(operation::Error::BadRequest {
+                reason: format!(
+                    "Taxonomy term of type {:?} cannot be created below a term of type {:?}",
+                    payload.taxonomy_type, parent.term_type
+                ),
+            });
+        }
+
         sqlx::query!(
             r#"
                 INSERT INTO uuid (trashed, discriminator)
@@ -508,8 +613,6 @@ impl TaxonomyTerm {
             reason: "Given target id is no taxonomy term".to_string(),
         })?;
         let term_type: TaxonomyType = taxonomy.term_type.parse()?;
-        let taxonomy_is_folder = term_type == TaxonomyType::CurriculumTopicFolder
-            || term_type == TaxonomyType::TopicFolder;
 
         let instance_id =
             Self::get_instance_id(payload.taxonomy_term_id, &mut *transaction).await?;
@@ -521,33 +624,13 @@ impl TaxonomyTerm {
                     reason: format!("entity with id {child_id} does not exist"),
                 })?;
 
-            match entity_type {
-                EntityType::CoursePage => {
-                    return Err(operation::Error::BadRequest {
-                        reason: format!(
-                            "entity with id {child_id} cannot be linked to a taxonomy term"
-                        ),
-                    })
-                }
-                EntityType::Exercise | EntityType::ExerciseGroup => {
-                    if !taxonomy_is_folder {
-                        return Err(operation::Error::BadRequest {
-                            reason: format!(
-                                "entity with id {child_id} is an exercise or exercise group and can only be linked to a topic folder"
-                            )
-                        });
-                    }
-                }
-                _ => {
-                    if taxonomy_is_folder {
-                        return Err(operation::Error::BadRequest {
-                            reason: format!(
-                                "entity with id {child_id} cannot be linked to a topic folder"
-                            ),
-                        });
-                    }
-                }
-            };
+            if !term_type.allows_entity_type(&entity_type) {
+                return Err(operation::Error::BadRequest {
+                    reason: format!(
+                        "entity with id {child_id} of type {entity_type:?} cannot be linked to a taxonomy term of type {term_type:?}"
+                    ),
+                });
+            }
 
             let is_child_already_linked_to_taxonomy = sqlx::query!(
                 r#"
@@ -695,6 +778,700 @@ impl TaxonomyTerm {
         Ok(())
     }
 
+    pub async fn move_terms<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &taxonomy_term_move_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        let destination = Self::fetch_type_and_position(payload.destination, &mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: format!(
+                    "Taxonomy term with id {} does not exist",
+                    payload.destination
+                ),
+            })?;
+
+        for id in &payload.ids {
+            let term = Self::fetch_type_and_position(*id, &mut *transaction)
+                .await?
+                .ok_or(operation::Error::BadRequest {
+                    reason: format!("Taxonomy term with id {id} does not exist"),
+                })?;
+
+            let previous_parent_id = term.parent_id.ok_or(operation::Error::BadRequest {
+                reason: format!("Taxonomy term with id {id} is a root and cannot be moved"),
+            })?;
+
+            if previous_parent_id == payload.destination {
+                continue;
+            }
+
+            if term.instance_id != destination.instance_id {
+                return Err(operation::Error::BadRequest {
+                    reason: format!(
+                        "Taxonomy term with id {id} cannot be moved to another instance"
+                    ),
+                });
+            }
+
+            if !term.term_type.allows_parent(&destination.term_type) {
+                return Err(operation::Error::BadRequest {
+                    reason: format!(
+                        "Taxonomy term with id {id} cannot be moved below a term of type {:?}",
+                        destination.term_type
+                    ),
+                });
+            }
+
+            let creates_cycle = sqlx::query!(
+                r#"
+                    WITH RECURSIVE ancestors AS (
+                        SELECT id, parent_id
+                        FROM term_taxonomy
+                        WHERE id = ?
+
+                        UNION
+
+                        SELECT parent.id, parent.parent_id
+                        FROM term_taxonomy parent
+                        JOIN ancestors ON ancestors.parent_id = parent.id
+                    )
+                    SELECT COUNT(*) AS count FROM ancestors WHERE id = ?
+                "#,
+                payload.destination,
+                id
+            )
+            .fetch_one(&mut *transaction)
+            .await?
+            .count
+                > 0;
+
+            if creates_cycle {
+                return Err(operation::Error::BadRequest {
+                    reason: format!(
+                        "Taxonomy term with id {id} cannot be moved below itself or its descendants"
+                    ),
+                });
+            }
+
+            let heaviest_weight = sqlx::query!(
+                r#"
+                    SELECT IFNULL(MAX(tt.weight), 0) AS current_heaviest
+                        FROM term_taxonomy tt
+                        WHERE tt.parent_id = ?
+                "#,
+                payload.destination,
+            )
+            .fetch_one(&mut *transaction)
+            .await?
+            .current_heaviest as i32
+                + 1;
+
+            sqlx::query!(
+                r#"
+                    UPDATE term_taxonomy
+                    SET parent_id = ?, weight = ?
+                    WHERE id = ?
+                "#,
+                payload.destination,
+                heaviest_weight,
+                id,
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            SetTaxonomyParentEventPayload::new(
+                *id,
+                previous_parent_id,
+                payload.destination,
+                payload.user_id,
+                term.instance_id,
+            )
+            .save(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn invalid_terms<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &invalid_taxonomy_terms_query::Payload,
+        acquire_from: A,
+    ) -> Result<invalid_taxonomy_terms_query::Output, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let allowed_type_pairs = serde_json::to_string(&TaxonomyType::allowed_type_pairs())?;
+
+        let records = sqlx::query!(
+            r#"
+                SELECT
+                    term_taxonomy.id,
+                    type.name AS term_type,
+                    term_taxonomy.parent_id,
+                    parent_type.name AS "parent_type?"
+                FROM term_taxonomy
+                JOIN uuid ON uuid.id = term_taxonomy.id
+                JOIN taxonomy ON taxonomy.id = term_taxonomy.taxonomy_id
+                JOIN type ON type.id = taxonomy.type_id
+                JOIN instance ON instance.id = taxonomy.instance_id
+                LEFT JOIN term_taxonomy parent ON parent.id = term_taxonomy.parent_id
+                LEFT JOIN taxonomy parent_taxonomy ON parent_taxonomy.id = parent.taxonomy_id
+                LEFT JOIN type parent_type ON parent_type.id = parent_taxonomy.type_id
+                WHERE uuid.trashed = 0
+                    AND (? IS NULL OR term_taxonomy.id > ?)
+                    AND (? IS NULL OR instance.subdomain = ?)
+                    AND (
+                        (term_taxonomy.parent_id IS NULL AND type.name != "root")
+                        OR (term_taxonomy.parent_id IS NOT NULL AND (
+                            parent_type.name IS NULL
+                            OR NOT JSON_CONTAINS(
+                                ?, JSON_QUOTE(CONCAT(type.name, "/", parent_type.name))
+                            )
+                        ))
+                    )
+                ORDER BY term_taxonomy.id
+                LIMIT ?
+            "#,
+            payload.after,
+            payload.after,
+            payload.instance,
+            payload.instance,
+            allowed_type_pairs,
+            payload.first + 1,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let has_next_page = records.len() as i32 > payload.first;
+
+        let terms = records
+            .into_iter()
+            .take(payload.first as usize)
+            .map(|record| {
+                let reason = match (record.parent_id, &record.parent_type) {
+                    (None, _) => "term without parent has to be of type root".to_string(),
+                    (Some(_), None) => "parent does not exist".to_string(),
+                    (Some(_), Some(parent_type)) => format!(
+                        "type {} is not allowed below type {}",
+                        record.term_type, parent_type
+                    ),
+                };
+
+                invalid_taxonomy_terms_query::InvalidTaxonomyTerm {
+                    id: record.id as i32,
+                    term_type: Self::normalize_type(&record.term_type),
+                    parent_id: record.parent_id.map(|id| id as i32),
+                    parent_type: record.parent_type.as_deref().map(Self::normalize_type),
+                    reason,
+                }
+            })
+            .collect();
+
+        Ok(invalid_taxonomy_terms_query::Output {
+            terms,
+            has_next_page,
+        })
+    }
+
+    pub async fn fetch_tree<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &taxonomy_tree_query::Payload,
+        acquire_from: A,
+    ) -> Result<taxonomy_tree_query::TaxonomyTreeNode, operation::Error> {
+        let mut connection = acquire_from.acquire().await?;
+        let include_trashed = payload.include_trashed.unwrap_or(false);
+
+        // `maxDepth` only limits the returned nodes. The transitive counts always cover the whole
+        // subtree of a node, and an entity linked to several terms of a subtree is counted once.
+        let records = sqlx::query!(
+            r#"
+                WITH RECURSIVE tree AS (
+                    SELECT id, 0 AS depth
+                    FROM term_taxonomy
+                    WHERE id = ?
+
+                    UNION ALL
+
+                    SELECT child.id, tree.depth + 1
+                    FROM term_taxonomy child
+                    JOIN tree ON tree.id = child.parent_id
+                    JOIN uuid child_uuid ON child_uuid.id = child.id
+                    WHERE tree.depth < ?
+                        AND (? OR child_uuid.trashed = 0)
+                ),
+                subtree AS (
+                    SELECT id AS ancestor_id, id
+                    FROM tree
+
+                    UNION ALL
+
+                    SELECT subtree.ancestor_id, child.id
+                    FROM term_taxonomy child
+                    JOIN subtree ON subtree.id = child.parent_id
+                    JOIN uuid child_uuid ON child_uuid.id = child.id
+                    WHERE ? OR child_uuid.trashed = 0
+                ),
+                entity_counts AS (
+                    SELECT
+                        subtree.ancestor_id AS term_taxonomy_id,
+                        entity_type.name AS entity_type,
+                        COUNT(DISTINCT CASE WHEN subtree.id = subtree.ancestor_id THEN entity.id END)
+                            AS direct_count,
+                        COUNT(DISTINCT entity.id) AS transitive_count
+                    FROM subtree
+                    JOIN term_taxonomy_entity tte ON tte.term_taxonomy_id = subtree.id
+                    JOIN entity ON entity.id = tte.entity_id
+                    JOIN uuid entity_uuid ON entity_uuid.id = entity.id
+                    JOIN type entity_type ON entity_type.id = entity.type_id
+                    WHERE ? OR entity_uuid.trashed = 0
+                    GROUP BY subtree.ancestor_id, entity_type.name
+                )
+                SELECT
+                    tree.id,
+                    tree.depth,
+                    term_taxonomy.parent_id,
+                    term.name,
+                    type.name AS term_type,
+                    term_taxonomy.weight,
+                    uuid.trashed,
+                    (
+                        SELECT JSON_OBJECTAGG(entity_counts.entity_type, entity_counts.direct_count)
+                        FROM entity_counts
+                        WHERE entity_counts.term_taxonomy_id = tree.id
+                            AND entity_counts.direct_count > 0
+                    ) AS direct_entity_counts,
+                    (
+                        SELECT JSON_OBJECTAGG(entity_counts.entity_type, entity_counts.transitive_count)
+                        FROM entity_counts
+                        WHERE entity_counts.term_taxonomy_id = tree.id
+                    ) AS transitive_entity_counts
+                FROM tree
+                JOIN term_taxonomy ON term_taxonomy.id = tree.id
+                JOIN term ON term.id = term_taxonomy.term_id
+                JOIN taxonomy ON taxonomy.id = term_taxonomy.taxonomy_id
+                JOIN type ON type.id = taxonomy.type_id
+                JOIN uuid ON uuid.id = tree.id
+                ORDER BY tree.depth, term_taxonomy.weight, tree.id
+            "#,
+            payload.root_id,
+            payload.max_depth.unwrap_or(i32::MAX),
+            include_trashed,
+            include_trashed,
+            include_trashed,
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        let nodes: Vec<(Option<i32>, taxonomy_tree_query::TaxonomyTreeNode)> = records
+            .into_iter()
+            .map(|record| {
+                let parent_id = match record.depth {
+                    0 => None,
+                    _ => record.parent_id.map(|id| id as i32),
+                };
+
+                (
+                    parent_id,
+                    taxonomy_tree_query::TaxonomyTreeNode {
+                        id: record.id as i32,
+                        name: record.name,
+                        term_type: Self::normalize_type(&record.term_type),
+                        weight: record.weight.unwrap_or(0),
+                        trashed: record.trashed != 0,
+                        direct_entity_counts: Self::parse_entity_counts(
+                            record.direct_entity_counts.as_ref(),
+                        ),
+                        transitive_entity_counts: Self::parse_entity_counts(
+                            record.transitive_entity_counts.as_ref(),
+                        ),
+                        children: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        assemble_tree(nodes).ok_or(operation::Error::NotFoundError)
+    }
+
+    fn parse_entity_counts(counts: Option<&serde_json::Value>) -> HashMap<String, i32> {
+        counts
+            .and_then(|counts| counts.as_object())
+            .map(|counts| {
+                counts
+                    .iter()
+                    .filter_map(|(entity_type, count)| {
+                        let typename =
+                            serde_json::to_value(entity_type.parse::<EntityType>().ok()?).ok()?;
+                        Some((typename.as_str()?.to_string(), count.as_i64()? as i32))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Moves all entity links, child terms, aliases and subscriptions of the source term to the
+    /// target term and trashes the source term afterwards.
+    pub async fn merge<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        payload: &taxonomy_term_merge_mutation::Payload,
+        acquire_from: A,
+    ) -> Result<(), operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        if payload.source_id == payload.target_id {
+            return Err(operation::Error::BadRequest {
+                reason: "a taxonomy term cannot be merged into itself".to_string(),
+            });
+        }
+
+        let source = Self::fetch_type_and_position(payload.source_id, &mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: format!("Taxonomy term with id {} does not exist", payload.source_id),
+            })?;
+        let target = Self::fetch_type_and_position(payload.target_id, &mut *transaction)
+            .await?
+            .ok_or(operation::Error::BadRequest {
+                reason: format!("Taxonomy term with id {} does not exist", payload.target_id),
+            })?;
+
+        let trashed_term = sqlx::query!(
+            r#"
+                SELECT id FROM uuid
+                WHERE id IN (?, ?) AND trashed = 1
+                LIMIT 1
+            "#,
+            payload.source_id,
+            payload.target_id
+        )
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        if let Some(term) = trashed_term {
+            return Err(operation::Error::BadRequest {
+                reason: format!("Taxonomy term with id {} is trashed", term.id),
+            });
+        }
+
+        if source.parent_id.is_none() {
+            return Err(operation::Error::BadRequest {
+                reason: "a root taxonomy term cannot be merged".to_string(),
+            });
+        }
+
+        if source.instance_id != target.instance_id {
+            return Err(operation::Error::BadRequest {
+                reason: "taxonomy terms of different instances cannot be merged".to_string(),
+            });
+        }
+
+        let target_is_descendant = sqlx::query!(
+            r#"
+                WITH RECURSIVE ancestors AS (
+                    SELECT id, parent_id
+                    FROM term_taxonomy
+                    WHERE id = ?
+
+                    UNION
+
+                    SELECT parent.id, parent.parent_id
+                    FROM term_taxonomy parent
+                    JOIN ancestors ON ancestors.parent_id = parent.id
+                )
+                SELECT COUNT(*) AS count FROM ancestors WHERE id = ?
+            "#,
+            payload.target_id,
+            payload.source_id
+        )
+        .fetch_one(&mut *transaction)
+        .await?
+        .count
+            > 0;
+
+        if target_is_descendant {
+            return Err(operation::Error::BadRequest {
+                reason: "a taxonomy term cannot be merged into one of its descendants".to_string(),
+            });
+        }
+
+        let children = sqlx::query!(
+            r#"
+                SELECT term_taxonomy.id, type.name AS term_type
+                FROM term_taxonomy
+                JOIN taxonomy ON taxonomy.id = term_taxonomy.taxonomy_id
+                JOIN type ON type.id = taxonomy.type_id
+                WHERE term_taxonomy.parent_id = ?
+                ORDER BY term_taxonomy.weight, term_taxonomy.id
+            "#,
+            payload.source_id
+        )
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        for child in &children {
+            let child_type: TaxonomyType = child.term_type.parse()?;
+            if !child_type.allows_parent(&target.term_type) {
+                return Err(operation::Error::BadRequest {
+                    reason: format!(
+                        "child {} of type {child_type:?} cannot be moved below a term of type {:?}",
+                        child.id, target.term_type
+                    ),
+                });
+            }
+        }
+
+        let entities = sqlx::query!(
+            r#"
+                SELECT tte.entity_id, type.name AS entity_type
+                FROM term_taxonomy_entity tte
+                JOIN entity ON entity.id = tte.entity_id
+                JOIN type ON type.id = entity.type_id
+                WHERE tte.term_taxonomy_id = ?
+                ORDER BY tte.position, tte.entity_id
+            "#,
+            payload.source_id
+        )
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        for entity in &entities {
+            let entity_type: EntityType = entity.entity_type.parse()?;
+            if !target.term_type.allows_entity_type(&entity_type) {
+                return Err(operation::Error::BadRequest {
+                    reason: format!(
+                        "entity {} of type {entity_type:?} cannot be linked to a term of type {:?}",
+                        entity.entity_id, target.term_type
+                    ),
+                });
+            }
+        }
+
+        let source_alias = Self::fetch(payload.source_id, &mut *transaction)
+            .await?
+            .alias;
+
+        let mut last_position = sqlx::query!(
+            r#"
+                SELECT IFNULL(MAX(position), 0) AS current_last
+                    FROM term_taxonomy_entity
+                    WHERE term_taxonomy_id = ?
+            "#,
+            payload.target_id
+        )
+        .fetch_one(&mut *transaction)
+        .await?
+        .current_last as i32;
+
+        for entity in &entities {
+            let entity_id = entity.entity_id as i32;
+
+            let is_linked_to_target = sqlx::query!(
+                r#"
+                    SELECT id FROM term_taxonomy_entity
+                        WHERE entity_id = ?
+                        AND term_taxonomy_id = ?
+                "#,
+                entity_id,
+                payload.target_id
+            )
+            .fetch_optional(&mut *transaction)
+            .await?
+            .is_some();
+
+            if !is_linked_to_target {
+                last_position += 1;
+
+                sqlx::query!(
+                    r#"
+                        INSERT INTO term_taxonomy_entity (entity_id, term_taxonomy_id, position)
+                        VALUES (?, ?, ?)
+                    "#,
+                    entity_id,
+                    payload.target_id,
+                    last_position
+                )
+                .execute(&mut *transaction)
+                .await?;
+
+                CreateTaxonomyLinkEventPayload::new(
+                    entity_id,
+                    payload.target_id,
+                    payload.user_id,
+                    target.instance_id,
+                )
+                .save(&mut *transaction)
+                .await?;
+            }
+
+            sqlx::query!(
+                r#"
+                    DELETE FROM term_taxonomy_entity
+                        WHERE entity_id = ?
+                        AND term_taxonomy_id = ?
+                "#,
+                entity_id,
+                payload.source_id
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            RemoveTaxonomyLinkEventPayload::new(
+                entity_id,
+                payload.source_id,
+                payload.user_id,
+                source.instance_id,
+            )
+            .save(&mut *transaction)
+            .await?;
+        }
+
+        let mut heaviest_weight = sqlx::query!(
+            r#"
+                SELECT IFNULL(MAX(tt.weight), 0) AS current_heaviest
+                    FROM term_taxonomy tt
+                    WHERE tt.parent_id = ?
+            "#,
+            payload.target_id,
+        )
+        .fetch_one(&mut *transaction)
+        .await?
+        .current_heaviest as i32;
+
+        for child in &children {
+            heaviest_weight += 1;
+
+            sqlx::query!(
+                r#"
+                    UPDATE term_taxonomy
+                    SET parent_id = ?, weight = ?
+                    WHERE id = ?
+                "#,
+                payload.target_id,
+                heaviest_weight,
+                child.id,
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            SetTaxonomyParentEventPayload::new(
+                child.id as i32,
+                payload.source_id,
+                payload.target_id,
+                payload.user_id,
+                source.instance_id,
+            )
+            .save(&mut *transaction)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"
+                INSERT INTO subscription (uuid_id, user_id, notify_mailman, date)
+                    SELECT ?, source.user_id, source.notify_mailman, source.date
+                    FROM subscription source
+                    WHERE source.uuid_id = ?
+                ON DUPLICATE KEY UPDATE
+                    notify_mailman = subscription.notify_mailman OR source.notify_mailman
+            "#,
+            payload.target_id,
+            payload.source_id,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"DELETE FROM subscription WHERE uuid_id = ?"#,
+            payload.source_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        // Existing aliases of the source now point to the target. Only when the source had no
+        // stored alias we add one, so that its path keeps resolving.
+        let moved_aliases = sqlx::query!(
+            r#"UPDATE url_alias SET uuid_id = ? WHERE uuid_id = ?"#,
+            payload.target_id,
+            payload.source_id,
+        )
+        .execute(&mut *transaction)
+        .await?
+        .rows_affected();
+
+        if moved_aliases == 0 {
+            sqlx::query!(
+                r#"
+                    INSERT INTO url_alias (instance_id, uuid_id, source, alias, timestamp)
+                        VALUES (?, ?, ?, ?, ?)
+                "#,
+                source.instance_id,
+                payload.target_id,
+                format!("/taxonomy/term/get/{}", payload.target_id),
+                source_alias.trim_start_matches('/'),
+                crate::datetime::DateTime::now(),
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        let instance: Instance = sqlx::query!(
+            r#"SELECT subdomain FROM instance WHERE id = ?"#,
+            source.instance_id
+        )
+        .fetch_one(&mut *transaction)
+        .await?
+        .subdomain
+        .parse()
+        .map_err(|error| operation::Error::InternalServerError {
+            error: Box::new(error),
+        })?;
+
+        Uuid::set_state(payload.source_id, true, &mut *transaction).await?;
+
+        SetUuidStateEventPayload::new(true, payload.user_id, payload.source_id, instance)
+            .save(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn fetch_type<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        id: i32,
+        executor: E,
+    ) -> Result<Option<TaxonomyType>, operation::Error> {
+        Ok(Self::fetch_type_and_position(id, executor)
+            .await?
+            .map(|term| term.term_type))
+    }
+
+    async fn fetch_type_and_position<'a, E: sqlx::Executor<'a, Database = sqlx::MySql>>(
+        id: i32,
+        executor: E,
+    ) -> Result<Option<TermTypeAndPosition>, operation::Error> {
+        match sqlx::query!(
+            r#"
+                SELECT type.name AS term_type, taxonomy.instance_id, term_taxonomy.parent_id
+                FROM term_taxonomy
+                JOIN taxonomy ON taxonomy.id = term_taxonomy.taxonomy_id
+                JOIN type ON type.id = taxonomy.type_id
+                WHERE term_taxonomy.id = ?
+            "#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?
+        {
+            Some(term) => Ok(Some(TermTypeAndPosition {
+                term_type: term.term_type.parse()?,
+                instance_id: term.instance_id,
+                parent_id: term.parent_id.map(|id| id as i32),
+            })),
+            None => Ok(None),
+        }
+    }
+
     pub async fn sort<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
         payload: &taxonomy_sort_mutation::Payload,
         acquire_from: A,
@@ -805,3 +1582,45 @@ impl TaxonomyTerm {
 }
 
 impl AssertExists for TaxonomyTerm {}
+
+impl TreeNode for taxonomy_tree_query::TaxonomyTreeNode {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn children_mut(&mut self) -> &mut Vec<Self> {
+        &mut self.children
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TaxonomyType;
+    use crate::uuid::EntityType;
+
+    #[test]
+    fn allows_topics_below_subjects_and_topics_only() {
+        assert!(TaxonomyType::Topic.allows_parent(&TaxonomyType::Subject));
+        assert!(TaxonomyType::Topic.allows_parent(&TaxonomyType::Topic));
+        assert!(!TaxonomyType::Topic.allows_parent(&TaxonomyType::TopicFolder));
+        assert!(!TaxonomyType::Subject.allows_parent(&TaxonomyType::TopicFolder));
+    }
+
+    #[test]
+    fn allows_exercises_in_folders_only() {
+        assert!(TaxonomyType::TopicFolder.allows_entity_type(&EntityType::Exercise));
+        assert!(!TaxonomyType::TopicFolder.allows_entity_type(&EntityType::Article));
+        assert!(!TaxonomyType::Topic.allows_entity_type(&EntityType::ExerciseGroup));
+        assert!(TaxonomyType::Forum.allows_entity_type(&EntityType::Article));
+        assert!(!TaxonomyType::Forum.allows_entity_type(&EntityType::CoursePage));
+    }
+
+    #[test]
+    fn lists_allowed_type_pairs_with_database_names() {
+        let pairs = TaxonomyType::allowed_type_pairs();
+
+        assert!(pairs.contains(&"topic-folder/topic".to_string()));
+        assert!(pairs.contains(&"curriculum-topic/curriculum-topic".to_string()));
+        assert!(!pairs.iter().any(|pair| pair.starts_with("root/")));
+    }
+}
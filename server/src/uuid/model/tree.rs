@@ -0,0 +1,25 @@
+/// A node of a tree which is loaded from the database as a flat list of rows.
+pub trait TreeNode: Sized {
+    fn id(&self) -> i32;
+    fn children_mut(&mut self) -> &mut Vec<Self>;
+}
+
+/// Assembles a list of nodes (each paired with the id of its parent) into a tree and returns its
+/// root. The nodes must be ordered by depth with the root first, and siblings must be in the order
+/// in which they shall appear in `children`.
+pub fn assemble_tree<T: TreeNode>(mut nodes: Vec<(Option<i32>, T)>) -> Option<T> {
+    // Attaching the nodes in reverse order moves every node into its parent before the parent
+    // itself is attached.
+    while nodes.len() > 1 {
+        let (parent_id, node) = nodes.pop()?;
+        if let Some((_, parent)) = nodes
+            .iter_mut()
+            .rev()
+            .find(|(_, parent)| Some(parent.id()) == parent_id)
+        {
+            parent.children_mut().insert(0, node);
+        }
+    }
+
+    nodes.pop().map(|(_, root)| root)
+}
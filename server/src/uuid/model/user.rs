@@ -2,7 +2,7 @@ use async_trait::async_trait;
 
 use serde::Serialize;
 
-use super::{ConcreteUuid, Uuid, UuidError, UuidFetcher};
+use super::{AssertExists, ConcreteUuid, Uuid, UuidError, UuidFetcher};
 use crate::datetime::DateTime;
 use crate::format_alias;
 
@@ -91,3 +91,5 @@ impl User {
         Some("user".to_string())
     }
 }
+
+impl AssertExists for User {}
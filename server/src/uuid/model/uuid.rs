@@ -1,13 +1,13 @@
 use crate::operation;
-use crate::uuid::messages::uuid_set_state_mutation;
+use crate::uuid::messages::{uuid_paths_query, uuid_set_state_mutation};
 use async_trait::async_trait;
 use serde::Serialize;
 use thiserror::Error;
 
 use super::discriminator::Discriminator;
 use super::{
-    comment::Comment, entity::Entity, entity_revision::EntityRevision, page::Page,
-    page_revision::PageRevision, taxonomy_term::TaxonomyTerm, user::User,
+    comment::Comment, entity::Entity, entity::EntityType, entity_revision::EntityRevision,
+    page::Page, page_revision::PageRevision, taxonomy_term::TaxonomyTerm, user::User,
 };
 use crate::event::SetUuidStateEventPayload;
 use crate::instance::Instance;
@@ -179,6 +179,209 @@ impl Uuid {
     pub fn get_alias(&self) -> String {
         self.alias.clone()
     }
+
+    /// Returns all paths from a root taxonomy term to the given uuid. Comments are resolved via
+    /// the object of their thread, entity and page revisions via their repository and child
+    /// entities (like course pages or grouped exercises) via their first parent, so that every
+    /// path ends in a taxonomy term linked to an entity. Uuids outside of the taxonomy (like pages
+    /// or users) have a single path without taxonomy terms.
+    ///
+    /// The canonical path is the first path which contains no trashed taxonomy term, where paths
+    /// are ordered by the creation of their taxonomy link (like `canonicalSubjectId` of entities).
+    /// When every path contains a trashed term, the first path is canonical.
+    pub async fn fetch_paths<'a, A: sqlx::Acquire<'a, Database = sqlx::MySql>>(
+        id: i32,
+        acquire_from: A,
+    ) -> Result<uuid_paths_query::Output, operation::Error> {
+        let mut transaction = acquire_from.begin().await?;
+
+        let mut trail: Vec<uuid_paths_query::Node> = Vec::new();
+        let mut current_id = id;
+
+        let term_ids: Vec<i32> = loop {
+            let discriminator = get_discriminator(current_id, &mut *transaction).await?;
+            let trashed = sqlx::query!("SELECT trashed FROM uuid WHERE id = ?", current_id)
+                .fetch_one(&mut *transaction)
+                .await?
+                .trashed
+                != 0;
+            let node = move |typename: String| uuid_paths_query::Node {
+                id: current_id,
+                typename,
+                name: None,
+                trashed,
+            };
+
+            match discriminator {
+                Discriminator::TaxonomyTerm => break vec![current_id],
+                Discriminator::Comment => {
+                    trail.push(node("Comment".to_string()));
+
+                    let comment = sqlx::query!(
+                        "SELECT parent_id, uuid_id FROM comment WHERE id = ?",
+                        current_id
+                    )
+                    .fetch_one(&mut *transaction)
+                    .await?;
+
+                    match comment.parent_id.or(comment.uuid_id) {
+                        Some(object_id) => current_id = object_id as i32,
+                        None => break Vec::new(),
+                    }
+                }
+                Discriminator::EntityRevision => {
+                    let revision = sqlx::query!(
+                        r#"
+                            SELECT r.repository_id, type.name AS entity_type
+                            FROM entity_revision r
+                            JOIN entity e ON e.id = r.repository_id
+                            JOIN type ON type.id = e.type_id
+                            WHERE r.id = ?
+                        "#,
+                        current_id
+                    )
+                    .fetch_one(&mut *transaction)
+                    .await?;
+                    let entity_type: EntityType = revision.entity_type.parse()?;
+
+                    trail.push(node(format!("{entity_type:?}Revision")));
+                    current_id = revision.repository_id as i32;
+                }
+                Discriminator::PageRevision => {
+                    trail.push(node("PageRevision".to_string()));
+
+                    current_id = sqlx::query!(
+                        "SELECT page_repository_id FROM page_revision WHERE id = ?",
+                        current_id
+                    )
+                    .fetch_one(&mut *transaction)
+                    .await?
+                    .page_repository_id as i32;
+                }
+                Discriminator::Entity => {
+                    let entity = sqlx::query!(
+                        r#"
+                            SELECT type.name AS entity_type
+                            FROM entity e
+                            JOIN type ON type.id = e.type_id
+                            WHERE e.id = ?
+                        "#,
+                        current_id
+                    )
+                    .fetch_one(&mut *transaction)
+                    .await?;
+                    let entity_type: EntityType = entity.entity_type.parse()?;
+
+                    trail.push(node(format!("{entity_type:?}")));
+
+                    let parent = sqlx::query!(
+                        r#"
+                            SELECT parent_id FROM entity_link
+                                WHERE child_id = ?
+                                ORDER BY id
+                                LIMIT 1
+                        "#,
+                        current_id
+                    )
+                    .fetch_optional(&mut *transaction)
+                    .await?;
+
+                    match parent {
+                        Some(parent) => current_id = parent.parent_id as i32,
+                        None => {
+                            break sqlx::query!(
+                                r#"
+                                    SELECT term_taxonomy_id FROM term_taxonomy_entity
+                                        WHERE entity_id = ?
+                                        ORDER BY id
+                                "#,
+                                current_id
+                            )
+                            .fetch_all(&mut *transaction)
+                            .await?
+                            .into_iter()
+                            .map(|link| link.term_taxonomy_id as i32)
+                            .collect();
+                        }
+                    }
+                }
+                discriminator => {
+                    trail.push(node(format!("{discriminator:?}")));
+                    break Vec::new();
+                }
+            }
+        };
+
+        trail.reverse();
+
+        let mut term_paths: Vec<Vec<uuid_paths_query::Node>> =
+            term_ids.iter().map(|_| Vec::new()).collect();
+
+        let ancestors = sqlx::query!(
+            r#"
+                WITH RECURSIVE ancestors AS (
+                    SELECT links.position, term_taxonomy.id, term_taxonomy.parent_id, 0 AS depth
+                    FROM JSON_TABLE(?, "$[*]" COLUMNS (position FOR ORDINALITY, id INT PATH "$")) links
+                    JOIN term_taxonomy ON term_taxonomy.id = links.id
+
+                    UNION
+
+                    SELECT ancestors.position, parent.id, parent.parent_id, ancestors.depth + 1
+                    FROM term_taxonomy parent
+                    JOIN ancestors ON ancestors.parent_id = parent.id
+                )
+                SELECT ancestors.position, ancestors.id, term.name, uuid.trashed
+                FROM ancestors
+                JOIN term_taxonomy ON term_taxonomy.id = ancestors.id
+                JOIN term ON term.id = term_taxonomy.term_id
+                JOIN uuid ON uuid.id = ancestors.id
+                ORDER BY ancestors.position, ancestors.depth DESC
+            "#,
+            serde_json::to_string(&term_ids).expect("ids are serializable")
+        )
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        for term in ancestors {
+            term_paths[term.position as usize - 1].push(uuid_paths_query::Node {
+                id: term.id as i32,
+                typename: "TaxonomyTerm".to_string(),
+                name: Some(term.name),
+                trashed: term.trashed != 0,
+            });
+        }
+
+        let mut paths: Vec<uuid_paths_query::Path> = term_paths
+            .into_iter()
+            .map(|mut nodes| {
+                nodes.extend(trail.iter().cloned());
+                uuid_paths_query::Path {
+                    canonical: false,
+                    nodes,
+                }
+            })
+            .collect();
+
+        if paths.is_empty() {
+            paths.push(uuid_paths_query::Path {
+                canonical: false,
+                nodes: trail,
+            });
+        }
+
+        let canonical_index = paths
+            .iter()
+            .position(|path| {
+                !path
+                    .nodes
+                    .iter()
+                    .any(|node| node.trashed && node.typename == "TaxonomyTerm")
+            })
+            .unwrap_or(0);
+        paths[canonical_index].canonical = true;
+
+        Ok(uuid_paths_query::Output { paths })
+    }
 }
 
 impl Uuid {
@@ -0,0 +1,159 @@
+mod backlinks_query {
+    use test_utils::*;
+
+    #[actix_rt::test]
+    async fn lists_content_linking_to_uuid_after_checkout() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "PageAddRevisionMutation",
+            json!({
+                "pageId": 16256,
+                "content": r#"{"plugin":"rows","state":[{"plugin":"text","state":[{"type":"p","children":[{"type":"a","href":"https://de.serlo.org/mathe/18514/kurs","children":[{"text":"Kurs"}]}]}]}]}"#,
+                "title": "test title",
+                "userId": 1,
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("BacklinksQuery", json!({ "id": 18514 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({
+                "backlinks": [
+                    { "sourceId": 16256, "__typename": "Page", "path": "/mathe/18514/kurs" }
+                ]
+            }));
+    }
+
+    #[actix_rt::test]
+    async fn lists_entities_linking_to_uuid_after_checkout() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "EntityAddRevisionMutation",
+            json!({
+                "revisionType": "ArticleRevision",
+                "input": {
+                    "changes": "test changes",
+                    "entityId": 1503,
+                    "needsReview": false,
+                    "subscribeThis": false,
+                    "subscribeThisByEmail": false,
+                    "fields": {
+                        "content": r#"{"plugin":"rows","state":[{"plugin":"text","state":[{"type":"p","children":[{"type":"a","href":"/18514","children":[{"text":"Kurs"}]}]}]}]}"#,
+                        "title": "test title",
+                        "metaTitle": "test metaTitle",
+                        "metaDescription": "see [Kurs](/1855)",
+                    }
+                },
+                "userId": 1,
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("BacklinksQuery", json!({ "id": 18514 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({
+                "backlinks": [
+                    { "sourceId": 1503, "__typename": "Article", "path": "/18514" }
+                ]
+            }));
+
+        // Only the content is scanned for links, not the other fields of the revision
+        Message::new("BacklinksQuery", json!({ "id": 1855 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert!(!result["backlinks"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|link| link["sourceId"] == 1503));
+            });
+    }
+}
+
+mod broken_links_query {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn lists_links_to_missing_uuids() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "PageAddRevisionMutation",
+            json!({
+                "pageId": 16256,
+                "content": "[missing](/999999999)",
+                "title": "test title",
+                "userId": 1,
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("BrokenLinksQuery", json!({ "first": 10 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(
+                    result["brokenLinks"],
+                    json!([{
+                        "sourceId": 16256,
+                        "targetId": 999999999,
+                        "path": "/999999999",
+                        "reason": "missing"
+                    }])
+                );
+                assert_eq!(result["hasNextPage"], false);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("BrokenLinksQuery", json!({ "first": 10_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_negative() {
+        Message::new("BrokenLinksQuery", json!({ "first": -1 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
+
+mod backlinks_rebuild_mutation {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn indexes_entities_and_pages_in_batches() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new("BacklinksRebuildMutation", json!({ "first": 2 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["indexedIds"], 2);
+                assert_eq!(result["hasNextPage"], true);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("BacklinksRebuildMutation", json!({ "first": 1_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
@@ -0,0 +1,156 @@
+mod entity_contributors_query {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn returns_contributors_with_statistics() {
+        Message::new(
+            "EntityContributorsQuery",
+            json!({ "id": 1503, "first": 100 }),
+        )
+        .execute()
+        .await
+        .should_be_ok_with(|result| {
+            let contributors = result["contributors"].as_array().unwrap();
+            assert!(!contributors.is_empty());
+
+            for contributor in contributors {
+                assert!(contributor["username"].is_string());
+                assert!(contributor["editCount"].as_i64().unwrap() > 0);
+                assert!(
+                    contributor["acceptedRevisionCount"].as_i64().unwrap()
+                        + contributor["rejectedRevisionCount"].as_i64().unwrap()
+                        <= contributor["editCount"].as_i64().unwrap()
+                );
+                assert!(
+                    contributor["firstContributionDate"].as_str()
+                        <= contributor["lastContributionDate"].as_str()
+                );
+            }
+        });
+    }
+
+    #[actix_rt::test]
+    async fn counts_new_revisions_within_time_window() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_new_test_user(&mut *transaction).await.unwrap();
+        let since = "2020-01-01T00:00:00Z";
+
+        Message::new(
+            "EntityAddRevisionMutation",
+            json!({
+                "revisionType": "ArticleRevision",
+                "input": {
+                    "changes": "test changes",
+                    "entityId": 1503,
+                    "needsReview": true,
+                    "subscribeThis": false,
+                    "subscribeThisByEmail": false,
+                    "fields": { "content": TEST_CONTENT, "title": "test title" }
+                },
+                "userId": user_id
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "EntityContributorsQuery",
+            json!({ "id": 1503, "since": since, "first": 100 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            let contributor = result["contributors"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|contributor| contributor["userId"] == user_id)
+                .unwrap();
+
+            assert_eq!(contributor["editCount"], 1);
+            assert_eq!(contributor["acceptedRevisionCount"], 0);
+            assert_eq!(contributor["rejectedRevisionCount"], 0);
+        });
+    }
+
+    #[actix_rt::test]
+    async fn returns_empty_list_for_time_window_without_revisions() {
+        Message::new(
+            "EntityContributorsQuery",
+            json!({ "id": 1503, "until": "2000-01-01T00:00:00Z", "first": 100 }),
+        )
+        .execute()
+        .await
+        .should_be_ok_with_body(json!({ "contributors": [] }));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_date_is_malformed() {
+        Message::new(
+            "EntityContributorsQuery",
+            json!({ "id": 1503, "since": "yesterday", "first": 100 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_negative() {
+        Message::new(
+            "EntityContributorsQuery",
+            json!({ "id": 1503, "first": -1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
+
+mod subject_contributors_query {
+    use test_utils::*;
+
+    #[actix_rt::test]
+    async fn returns_contributors_ordered_by_edit_count() {
+        Message::new(
+            "SubjectContributorsQuery",
+            json!({ "subjectId": 5, "first": 10 }),
+        )
+        .execute()
+        .await
+        .should_be_ok_with(|result| {
+            let edit_counts: Vec<i64> = result["contributors"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|contributor| contributor["editCount"].as_i64().unwrap())
+                .collect();
+
+            assert!(!edit_counts.is_empty() && edit_counts.len() <= 10);
+            assert!(edit_counts.windows(2).all(|pair| pair[0] >= pair[1]));
+        });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new(
+            "SubjectContributorsQuery",
+            json!({ "subjectId": 5, "first": 10_001 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_negative() {
+        Message::new(
+            "SubjectContributorsQuery",
+            json!({ "subjectId": 5, "first": -1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
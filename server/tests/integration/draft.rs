@@ -0,0 +1,202 @@
+mod draft_create_mutation {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn creates_and_updates_draft() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "DraftCreateMutation",
+            json!({ "userId": 1, "repositoryId": 1503, "fields": { "title": "draft" } }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["repositoryId"], 1503);
+            assert_eq!(result["fields"], json!({ "title": "draft" }));
+        });
+
+        Message::new(
+            "DraftUpdateMutation",
+            json!({ "userId": 1, "repositoryId": 1503, "fields": { "title": "updated" } }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("DraftsQuery", json!({ "userId": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["drafts"], 1);
+                assert_eq!(result["drafts"][0]["fields"], json!({ "title": "updated" }));
+            });
+
+        Message::new(
+            "UnrevisedEntitiesQuery",
+            json!({ "entityType": "Article", "authorId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert!(!result["unrevisedEntityIds"]
+                .as_array()
+                .unwrap()
+                .contains(&json!(1503)));
+        });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_draft_already_exists() {
+        let mut transaction = begin_transaction().await;
+        let payload = json!({ "userId": 1, "repositoryId": 1503, "fields": {} });
+
+        Message::new("DraftCreateMutation", payload.clone())
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok();
+
+        Message::new("DraftCreateMutation", payload)
+            .execute_on(&mut transaction)
+            .await
+            .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_repository_is_no_entity_or_page() {
+        Message::new(
+            "DraftCreateMutation",
+            json!({ "userId": 1, "repositoryId": 1, "fields": {} }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
+
+mod draft_discard_mutation {
+    use test_utils::*;
+
+    #[actix_rt::test]
+    async fn discards_draft() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "DraftCreateMutation",
+            json!({ "userId": 1, "repositoryId": 16256, "fields": {} }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "DraftDiscardMutation",
+            json!({ "userId": 1, "repositoryId": 16256 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("DraftsQuery", json!({ "userId": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "drafts": [] }));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_draft_does_not_exist() {
+        Message::new(
+            "DraftDiscardMutation",
+            json!({ "userId": 1, "repositoryId": 16256 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
+
+mod draft_submit_mutation {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn submits_draft_as_entity_revision() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "DraftCreateMutation",
+            json!({
+                "userId": 1,
+                "repositoryId": 1503,
+                "fields": { "content": TEST_CONTENT, "title": "draft title" }
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        let revision_id = Message::new(
+            "DraftSubmitMutation",
+            json!({
+                "userId": 1,
+                "repositoryId": 1503,
+                "changes": "from draft",
+                "needsReview": true,
+                "subscribeThis": false,
+                "subscribeThisByEmail": false
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json()["revisionId"]
+            .clone();
+
+        Message::new("UuidQuery", json!({ "id": revision_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["title"], "draft title");
+                assert_eq!(result["changes"], "from draft");
+                assert_eq!(result["repositoryId"], 1503);
+            });
+
+        Message::new("DraftsQuery", json!({ "userId": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "drafts": [] }));
+    }
+
+    #[actix_rt::test]
+    async fn keeps_draft_when_fields_are_invalid() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "DraftCreateMutation",
+            json!({ "userId": 1, "repositoryId": 1503, "fields": { "title": "draft title" } }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "DraftSubmitMutation",
+            json!({
+                "userId": 1,
+                "repositoryId": 1503,
+                "changes": "from draft",
+                "needsReview": true,
+                "subscribeThis": false,
+                "subscribeThisByEmail": false
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request_with(|result| {
+            assert_eq!(result["fieldErrors"][0]["field"], "content");
+        });
+
+        Message::new("DraftsQuery", json!({ "userId": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert_has_length(&result["drafts"], 1));
+    }
+}
@@ -1,14 +1,200 @@
 mod unrevised_entities_query {
-    use test_utils::*;
+    use test_utils::{assert_eq, *};
 
     #[actix_rt::test]
     async fn returns_list_of_unrevised_entities() {
         Message::new("UnrevisedEntitiesQuery", json!({}))
             .execute()
             .await
-            .should_be_ok_with_body(
-                json!({ "unrevisedEntityIds": [26892, 33582, 34741, 34907, 35247, 35556] }),
-            );
+            .should_be_ok_with(|result| {
+                assert_eq!(
+                    result["unrevisedEntityIds"],
+                    json!([26892, 33582, 34741, 34907, 35247, 35556])
+                );
+                assert_eq!(result["hasNextPage"], false);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn returns_revision_summary_per_entity() {
+        Message::new("UnrevisedEntitiesQuery", json!({ "first": 1 }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                let entity = &result["unrevisedEntities"][0];
+
+                assert_eq!(entity["id"], 26892);
+                assert!(entity["revisionCount"].as_i64().unwrap() > 0);
+                assert!(entity["oldestRevisionDate"].is_string());
+                assert!(!entity["authorIds"].as_array().unwrap().is_empty());
+            });
+    }
+
+    #[actix_rt::test]
+    async fn paginates_with_first_and_after() {
+        let first_page = Message::new("UnrevisedEntitiesQuery", json!({ "first": 2 }))
+            .execute()
+            .await
+            .get_json();
+
+        assert_eq!(first_page["unrevisedEntityIds"], json!([26892, 33582]));
+        assert_eq!(first_page["hasNextPage"], true);
+
+        Message::new(
+            "UnrevisedEntitiesQuery",
+            json!({
+                "first": 2,
+                "after": first_page["unrevisedEntities"][1]["oldestRevisionId"]
+            }),
+        )
+        .execute()
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["unrevisedEntityIds"], json!([34741, 34907]));
+        });
+    }
+
+    #[actix_rt::test]
+    async fn filters_by_entity_type() {
+        let result = Message::new(
+            "UnrevisedEntitiesQuery",
+            json!({ "entityType": "text-exercise" }),
+        )
+        .execute()
+        .await
+        .get_json();
+
+        for id in result["unrevisedEntityIds"].as_array().unwrap() {
+            Message::new("UuidQuery", json!({ "id": id }))
+                .execute()
+                .await
+                .should_be_ok_with(|entity| assert_eq!(entity["__typename"], "Exercise"));
+        }
+    }
+
+    #[actix_rt::test]
+    async fn filters_by_instance() {
+        let de_ids = unrevised_entity_ids(
+            &Message::new("UnrevisedEntitiesQuery", json!({ "instance": "de" }))
+                .execute()
+                .await
+                .get_json(),
+        );
+        let en_ids = unrevised_entity_ids(
+            &Message::new("UnrevisedEntitiesQuery", json!({ "instance": "en" }))
+                .execute()
+                .await
+                .get_json(),
+        );
+
+        assert!(!de_ids.is_empty());
+
+        for (instance, ids) in [("de", &de_ids), ("en", &en_ids)] {
+            for id in ids {
+                Message::new("UuidQuery", json!({ "id": id }))
+                    .execute()
+                    .await
+                    .should_be_ok_with(|entity| assert_eq!(entity["instance"], instance));
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn filters_by_subject() {
+        let all_ids = Message::new("UnrevisedEntitiesQuery", json!({}))
+            .execute()
+            .await
+            .get_json()["unrevisedEntityIds"]
+            .clone();
+
+        for id in all_ids.as_array().unwrap() {
+            let subject_id = Message::new("UuidQuery", json!({ "id": id }))
+                .execute()
+                .await
+                .get_json()["canonicalSubjectId"]
+                .clone();
+
+            if subject_id.is_null() {
+                continue;
+            }
+
+            Message::new("UnrevisedEntitiesQuery", json!({ "subjectId": subject_id }))
+                .execute()
+                .await
+                .should_be_ok_with(|result| {
+                    assert!(unrevised_entity_ids(&result).contains(id));
+                });
+        }
+    }
+
+    #[actix_rt::test]
+    async fn filters_by_author() {
+        let entity = Message::new("UnrevisedEntitiesQuery", json!({ "first": 1 }))
+            .execute()
+            .await
+            .get_json()["unrevisedEntities"][0]
+            .clone();
+        let author_id = entity["authorIds"][0].clone();
+
+        Message::new("UnrevisedEntitiesQuery", json!({ "authorId": author_id }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                assert!(unrevised_entity_ids(&result).contains(&entity["id"]));
+
+                for entity in result["unrevisedEntities"].as_array().unwrap() {
+                    assert!(entity["authorIds"].as_array().unwrap().contains(&author_id));
+                }
+            });
+    }
+
+    #[actix_rt::test]
+    async fn filters_by_new_authors() {
+        let mut all_ids = unrevised_entity_ids(
+            &Message::new("UnrevisedEntitiesQuery", json!({}))
+                .execute()
+                .await
+                .get_json(),
+        );
+        let new_author_ids = unrevised_entity_ids(
+            &Message::new("UnrevisedEntitiesQuery", json!({ "isNewAuthor": true }))
+                .execute()
+                .await
+                .get_json(),
+        );
+        let other_ids = unrevised_entity_ids(
+            &Message::new("UnrevisedEntitiesQuery", json!({ "isNewAuthor": false }))
+                .execute()
+                .await
+                .get_json(),
+        );
+
+        assert!(new_author_ids.iter().all(|id| !other_ids.contains(id)));
+
+        let mut combined_ids = [new_author_ids, other_ids].concat();
+        combined_ids.sort_by_key(|id| id.as_i64());
+        all_ids.sort_by_key(|id| id.as_i64());
+        assert_eq!(combined_ids, all_ids);
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("UnrevisedEntitiesQuery", json!({ "first": 10_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_negative() {
+        Message::new("UnrevisedEntitiesQuery", json!({ "first": -1 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+
+    fn unrevised_entity_ids(result: &Value) -> Vec<Value> {
+        result["unrevisedEntityIds"].as_array().unwrap().clone()
     }
 }
 
@@ -176,6 +362,40 @@ mod add_revision_mutation {
         assert_eq!(revision["id"], new_revision["revisionId"]);
     }
 
+    #[actix_rt::test]
+    async fn fails_with_field_errors_when_fields_are_invalid() {
+        Message::new(
+            "EntityAddRevisionMutation",
+            json!({
+                "revisionType": "ArticleRevision",
+                "input": {
+                    "changes": "test changes",
+                    "entityId": 1503,
+                    "needsReview": true,
+                    "subscribeThis": false,
+                    "subscribeThisByEmail": false,
+                    "fields": {
+                        "content": "no editor state",
+                        "foo": "bar"
+                    }
+                },
+                "userId": 1
+            }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request_with(|result| {
+            assert_eq!(
+                result["fieldErrors"],
+                json!([
+                    { "field": "content", "reason": "field is not valid editor state" },
+                    { "field": "foo", "reason": "field is not allowed for this revision type" },
+                    { "field": "title", "reason": "field is required" }
+                ])
+            );
+        });
+    }
+
     async fn get_revisions(id: i32, transaction: &mut sqlx::Transaction<'_, sqlx::MySql>) -> Value {
         Message::new("UuidQuery", json!({ "id": id }))
             .execute_on(transaction)
@@ -220,15 +440,10 @@ mod create_mutation {
                     "taxonomyTermId": taxonomy_term_id,
                     "parentId": parent_id,
                     "needsReview": false,
-                    "fields": std::collections::HashMap::from([
-                        ("content", "I am a new exercise!"),
-                        ("description", "test description"),
-                        ("metaDescription", "test metaDescription"),
-                        ("metaTitle", "test metaTitle"),
-                        ("title", "test title"),
-                        ("url", "test url"),
-                        ("cohesive", "true"),
-                   ]),
+                    "fields": EntityTestWrapper::all()
+                        .iter()
+                        .find(|wrapper| wrapper.typename == entity_type)
+                        .map(|wrapper| wrapper.fields()),
                 },
                 "userId": 1,
             }),
@@ -327,13 +542,7 @@ mod create_mutation {
                     "parentId": Option::<i32>::None,
                     "needsReview": false,
                     "fields": std::collections::HashMap::from([
-                        ("content", "I am a new exercise!"),
-                        ("description", "test description"),
-                        ("metaDescription", "test metaDescription"),
-                        ("metaTitle", "test metaTitle"),
-                        ("title", "test title"),
-                        ("url", "test url"),
-                        ("cohesive", "true"),
+                        ("content", TEST_CONTENT),
                     ]),
                 },
                 "userId": 1,
@@ -407,7 +616,7 @@ mod create_mutation {
                     "taxonomyTermId": 7,
                     "needsReview": false,
                     "fields": {
-                        "content": "content",
+                        "content": TEST_CONTENT,
                         "title": "title",
                         "metaTitle": "metaTitle",
                         "metaDescription": "metaDescription"
@@ -435,7 +644,8 @@ mod create_mutation {
                     "parentId": 1,
                     "needsReview": true,
                     "fields": {
-                        "content": "content",
+                        "content": TEST_CONTENT,
+                        "title": "title",
                     },
                 },
                 "userId": 1_i32,
@@ -460,7 +670,7 @@ mod create_mutation {
                     "taxonomyTermId": 1,
                     "needsReview": true,
                     "fields": {
-                        "content": "content",
+                        "content": TEST_CONTENT,
                         "title": "title",
                         "metaTitle": "metaTitle",
                         "metaDescription": "metaDescription"
@@ -737,3 +947,214 @@ mod sort_mutation {
         .should_be_bad_request();
     }
 }
+
+mod bulk_set_license_mutation {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn sets_license_of_given_entities() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "EntityBulkSetLicenseMutation",
+            json!({ "userId": 1, "entityIds": [1495, 1503], "licenseId": 2 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({
+            "success": true,
+            "count": 2,
+            "entityIds": [1495, 1503]
+        }));
+
+        for entity_id in [1495, 1503] {
+            Message::new("UuidQuery", json!({ "id": entity_id }))
+                .execute_on(&mut transaction)
+                .await
+                .should_be_ok_with(|result| assert_eq!(result["licenseId"], 2));
+
+            Message::new("EventsQuery", json!({ "first": 1, "objectId": entity_id }))
+                .execute_on(&mut transaction)
+                .await
+                .should_be_ok_with(|result| {
+                    assert_json_include!(
+                        actual: &result["events"][0],
+                        expected: json!({
+                            "__typename": "SetLicenseNotificationEvent",
+                            "actorId": 1,
+                            "objectId": entity_id,
+                        })
+                    )
+                });
+        }
+    }
+
+    #[actix_rt::test]
+    async fn counts_entities_of_taxonomy_subtree_in_dry_run() {
+        let mut transaction = begin_transaction().await;
+
+        let result = Message::new(
+            "EntityBulkSetLicenseMutation",
+            json!({ "userId": 1, "taxonomyTermId": 1385, "licenseId": 2, "dryRun": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json();
+
+        assert!(result["count"].as_i64().unwrap() > 0);
+
+        let entity_id = &result["entityIds"][0];
+        Message::new("UuidQuery", json!({ "id": entity_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|entity| assert_ne!(entity["licenseId"], 2));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_neither_or_both_targets_are_given() {
+        Message::new(
+            "EntityBulkSetLicenseMutation",
+            json!({ "userId": 1, "licenseId": 2 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+
+        Message::new(
+            "EntityBulkSetLicenseMutation",
+            json!({ "userId": 1, "taxonomyTermId": 1385, "entityIds": [1495], "licenseId": 2 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_license_does_not_exist() {
+        Message::new(
+            "EntityBulkSetLicenseMutation",
+            json!({ "userId": 1, "entityIds": [1495], "licenseId": 0 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
+
+mod invalid_entity_revisions_query {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn reports_revisions_with_invalid_fields() {
+        let mut transaction = begin_transaction().await;
+
+        set_entity_revision_field(2218, "foo", "bar", &mut *transaction)
+            .await
+            .unwrap();
+
+        Message::new(
+            "InvalidEntityRevisionsQuery",
+            json!({ "first": 1, "after": 2217 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            let revision = &result["revisions"][0];
+
+            assert_eq!(revision["id"], 2218);
+            assert!(revision["errors"].as_array().unwrap().contains(&json!({
+                "field": "foo",
+                "reason": "field is not allowed for this revision type"
+            })));
+        });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("InvalidEntityRevisionsQuery", json!({ "first": 10_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
+
+mod entity_tree_query {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn returns_course_with_pages_in_sort_order() {
+        Message::new("EntityTreeQuery", json!({ "id": 18514 }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["id"], 18514);
+                assert_eq!(result["__typename"], "Course");
+                assert!(result["fields"]["title"].is_string());
+
+                let children = result["children"].as_array().unwrap();
+                assert!(children.iter().any(|child| child["id"] == 18521));
+                assert!(children
+                    .iter()
+                    .all(|child| child["__typename"] == "CoursePage"
+                        && child["trashed"] == false
+                        && child["children"] == json!([])));
+            });
+    }
+
+    #[actix_rt::test]
+    async fn follows_order_set_by_entity_sort_mutation() {
+        let mut transaction = begin_transaction().await;
+
+        let children_ids = [9911, 9919, 2233, 2225, 5075, 9899, 9907];
+
+        Message::new(
+            "EntitySortMutation",
+            json!({ "childrenIds": children_ids, "entityId": 2223 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new(
+            "EntityTreeQuery",
+            json!({ "id": 2223, "includeTrashed": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            let ids: Vec<_> = result["children"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|child| child["id"].clone())
+                .collect();
+            assert_eq!(to_value(ids).unwrap(), to_value(children_ids).unwrap());
+        });
+    }
+
+    #[actix_rt::test]
+    async fn returns_no_children_for_depth_zero() {
+        Message::new("EntityTreeQuery", json!({ "id": 18514, "depth": 0 }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["children"], json!([]));
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_depth_is_too_high() {
+        Message::new("EntityTreeQuery", json!({ "id": 18514, "depth": 6 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_id_is_not_an_entity() {
+        Message::new("EntityTreeQuery", json!({ "id": 1 }))
+            .execute()
+            .await
+            .should_be_not_found();
+    }
+}
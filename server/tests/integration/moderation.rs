@@ -0,0 +1,288 @@
+mod comment_moderation {
+    use test_utils::{assert_eq, *};
+
+    async fn create_thread(
+        user_id: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> i32 {
+        Message::new(
+            "ThreadCreateThreadMutation",
+            json!({
+                "title": "Title",
+                "content": "Buy cheap stuff!",
+                "objectId": 1565,
+                "userId": user_id,
+                "subscribe": false,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut *transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap() as i32
+    }
+
+    #[actix_rt::test]
+    async fn flagged_comment_shows_up_in_moderation_queue() {
+        let mut transaction = begin_transaction().await;
+        let comment_id = create_thread(1, &mut transaction).await;
+
+        Message::new(
+            "CommentFlagMutation",
+            json!({ "commentId": comment_id, "userId": 10, "reason": "spam" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new(
+            "CommentFlagMutation",
+            json!({ "commentId": comment_id, "userId": 2, "reason": "off-topic" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("ModerationQueueQuery", json!({ "first": 10_000 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                let comment = result["comments"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .find(|comment| comment["commentId"] == comment_id)
+                    .unwrap();
+                assert_eq!(comment["flagCount"], 2);
+                assert_eq!(comment["reasons"], json!(["spam", "off-topic"]));
+            });
+
+        Message::new(
+            "ModerationLogQuery",
+            json!({ "first": 1, "commentId": comment_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_json_include!(
+                actual: &result["entries"][0],
+                expected: json!({
+                    "commentId": comment_id,
+                    "actorId": 2,
+                    "action": "flag",
+                    "reason": "off-topic",
+                })
+            );
+            assert_eq!(result["hasNextPage"], true);
+        });
+    }
+
+    #[actix_rt::test]
+    async fn moderation_queue_is_paginated_by_comments() {
+        let mut transaction = begin_transaction().await;
+        let mut comment_ids = Vec::new();
+
+        for _ in 0..2 {
+            let comment_id = create_thread(1, &mut transaction).await;
+
+            for (user_id, reason) in [(10, "spam"), (2, "abusive")] {
+                Message::new(
+                    "CommentFlagMutation",
+                    json!({ "commentId": comment_id, "userId": user_id, "reason": reason }),
+                )
+                .execute_on(&mut transaction)
+                .await
+                .should_be_ok();
+            }
+
+            comment_ids.push(comment_id);
+        }
+
+        Message::new(
+            "ModerationQueueQuery",
+            json!({ "first": 1, "after": comment_ids[0] - 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_has_length(&result["comments"], 1);
+            assert_eq!(result["comments"][0]["commentId"], comment_ids[0]);
+            assert_eq!(result["comments"][0]["flagCount"], 2);
+            assert_eq!(result["hasNextPage"], true);
+        });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_comment_is_flagged_twice_by_same_user() {
+        let mut transaction = begin_transaction().await;
+        let comment_id = create_thread(1, &mut transaction).await;
+
+        for expected_ok in [true, false] {
+            let response = Message::new(
+                "CommentFlagMutation",
+                json!({ "commentId": comment_id, "userId": 10, "reason": "abusive" }),
+            )
+            .execute_on(&mut transaction)
+            .await;
+
+            if expected_ok {
+                response.should_be_ok();
+            } else {
+                response.should_be_bad_request();
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_flagged_id_is_no_comment() {
+        Message::new(
+            "CommentFlagMutation",
+            json!({ "commentId": 1, "userId": 10, "reason": "other" }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_flagging_user_does_not_exist() {
+        let mut transaction = begin_transaction().await;
+        let comment_id = create_thread(1, &mut transaction).await;
+
+        Message::new(
+            "CommentFlagMutation",
+            json!({ "commentId": comment_id, "userId": 999_999, "reason": "other" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_author_of_trashed_comments_does_not_exist() {
+        Message::new(
+            "UserTrashCommentsMutation",
+            json!({ "authorId": 999_999, "userId": 1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn marks_comment_as_spam_and_trashes_it() {
+        let mut transaction = begin_transaction().await;
+        let comment_id = create_thread(1, &mut transaction).await;
+
+        Message::new(
+            "CommentFlagMutation",
+            json!({ "commentId": comment_id, "userId": 10, "reason": "spam" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "CommentMarkAsSpamMutation",
+            json!({ "commentId": comment_id, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("UuidQuery", json!({ "id": comment_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert_eq!(result["trashed"], true));
+
+        Message::new("ModerationQueueQuery", json!({ "first": 10_000 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                let ids: Vec<_> = result["comments"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|comment| comment["commentId"].clone())
+                    .collect();
+                assert!(!ids.contains(&json!(comment_id)));
+            });
+
+        Message::new(
+            "ModerationLogQuery",
+            json!({ "first": 1, "action": "spam" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["entries"][0]["commentId"], comment_id);
+            assert_eq!(result["entries"][0]["actorId"], 1);
+        });
+    }
+
+    #[actix_rt::test]
+    async fn trashes_all_comments_of_user() {
+        let mut transaction = begin_transaction().await;
+        let author_id = create_new_test_user(&mut *transaction).await.unwrap();
+        let first_comment_id = create_thread(author_id, &mut transaction).await;
+        let second_comment_id = create_thread(author_id, &mut transaction).await;
+
+        Message::new(
+            "UserTrashCommentsMutation",
+            json!({ "authorId": author_id, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({
+            "success": true,
+            "trashedCommentIds": [first_comment_id, second_comment_id]
+        }));
+
+        for comment_id in [first_comment_id, second_comment_id] {
+            Message::new("UuidQuery", json!({ "id": comment_id }))
+                .execute_on(&mut transaction)
+                .await
+                .should_be_ok_with(|result| assert_eq!(result["trashed"], true));
+        }
+
+        Message::new(
+            "ModerationLogQuery",
+            json!({ "first": 10, "actorId": 1, "action": "trash" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["entries"][0]["commentId"], second_comment_id);
+            assert_eq!(result["entries"][1]["commentId"], first_comment_id);
+        });
+
+        Message::new(
+            "UserTrashCommentsMutation",
+            json!({ "authorId": author_id, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true, "trashedCommentIds": [] }));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        for message in ["ModerationLogQuery", "ModerationQueueQuery"] {
+            Message::new(message, json!({ "first": 10_001 }))
+                .execute()
+                .await
+                .should_be_bad_request();
+        }
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_negative() {
+        for message in ["ModerationLogQuery", "ModerationQueueQuery"] {
+            Message::new(message, json!({ "first": -1 }))
+                .execute()
+                .await
+                .should_be_bad_request();
+        }
+    }
+}
@@ -0,0 +1,637 @@
+mod notifications_query {
+    use test_utils::{assert_eq, *};
+
+    /// Creates a user with two unread notifications about new comments in a thread.
+    async fn create_user_with_notifications(
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> i32 {
+        let user_id = create_new_test_user(&mut **transaction).await.unwrap();
+
+        let thread_id = Message::new(
+            "ThreadCreateThreadMutation",
+            json!({
+                "title": "Title",
+                "content": "This is content.",
+                "objectId": 1565,
+                "userId": user_id,
+                "subscribe": true,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut *transaction)
+        .await
+        .get_json()["id"]
+            .clone();
+
+        for content in ["first answer", "second answer"] {
+            Message::new(
+                "ThreadCreateCommentMutation",
+                json!({
+                    "threadId": thread_id,
+                    "userId": 1,
+                    "content": content,
+                    "subscribe": false,
+                    "sendEmail": false,
+                }),
+            )
+            .execute_on(&mut *transaction)
+            .await
+            .should_be_ok();
+        }
+
+        user_id
+    }
+
+    #[actix_rt::test]
+    async fn paginates_notifications() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_user_with_notifications(&mut transaction).await;
+
+        let first_page = Message::new(
+            "NotificationsQuery",
+            json!({ "userId": user_id, "first": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json();
+        assert_has_length(&first_page["notifications"], 1);
+        assert_eq!(first_page["hasNextPage"], true);
+
+        Message::new(
+            "NotificationsQuery",
+            json!({
+                "userId": user_id,
+                "first": 1,
+                "after": first_page["notifications"][0]["id"]
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_has_length(&result["notifications"], 1);
+            assert_ne!(
+                result["notifications"][0]["id"],
+                first_page["notifications"][0]["id"]
+            );
+            assert_eq!(result["hasNextPage"], false);
+        });
+    }
+
+    #[actix_rt::test]
+    async fn filters_unread_notifications_and_event_types() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_user_with_notifications(&mut transaction).await;
+
+        let notification_id = Message::new("NotificationsQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["notifications"][0]["id"]
+            .clone();
+
+        Message::new(
+            "NotificationSetStateMutation",
+            json!({ "ids": [notification_id], "userId": user_id, "unread": false }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "NotificationsQuery",
+            json!({ "userId": user_id, "unreadOnly": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| assert_has_length(&result["notifications"], 1));
+
+        Message::new(
+            "NotificationsQuery",
+            json!({ "userId": user_id, "eventType": "CreateCommentNotificationEvent" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| assert_has_length(&result["notifications"], 2));
+
+        Message::new(
+            "NotificationsQuery",
+            json!({ "userId": user_id, "eventType": "CreateThreadNotificationEvent" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| assert_has_length(&result["notifications"], 0));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new(
+            "NotificationsQuery",
+            json!({ "userId": 1, "first": 10_001 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_negative() {
+        Message::new("NotificationsQuery", json!({ "userId": 1, "first": -1 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
+
+mod unread_notification_count_query {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn counts_unread_notifications_and_marks_all_as_read() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_new_test_user(&mut *transaction).await.unwrap();
+
+        sqlx::query!(
+            "INSERT INTO subscription (uuid_id, user_id, notify_mailman) VALUES (1565, ?, 0)",
+            user_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .unwrap();
+
+        Message::new(
+            "ThreadCreateThreadMutation",
+            json!({
+                "title": "Title",
+                "content": "This is content.",
+                "objectId": 1565,
+                "userId": 1,
+                "subscribe": false,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("UnreadNotificationCountQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "userId": user_id, "unreadCount": 1 }));
+
+        Message::new(
+            "NotificationSetAllReadMutation",
+            json!({ "userId": user_id, "eventType": "CreateCommentNotificationEvent" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("UnreadNotificationCountQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "userId": user_id, "unreadCount": 1 }));
+
+        Message::new(
+            "NotificationSetAllReadMutation",
+            json!({ "userId": user_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("UnreadNotificationCountQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "userId": user_id, "unreadCount": 0 }));
+    }
+
+    #[actix_rt::test]
+    async fn does_not_mark_hidden_notifications_as_read() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_new_test_user(&mut *transaction).await.unwrap();
+
+        let blog_post_event_id = sqlx::query!(
+            r#"
+                SELECT event_log.id FROM event_log
+                    JOIN uuid ON uuid.id = event_log.uuid_id
+                    WHERE uuid.discriminator = "blogPost"
+                    LIMIT 1
+            "#
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .unwrap()
+        .id;
+
+        let notification_id = sqlx::query!(
+            r#"
+                INSERT INTO notification (user_id, seen, date, email_sent, email)
+                    VALUES (?, 0, NOW(), 0, 0)
+            "#,
+            user_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .unwrap()
+        .last_insert_id();
+
+        sqlx::query!(
+            "INSERT INTO notification_event (notification_id, event_log_id) VALUES (?, ?)",
+            notification_id,
+            blog_post_event_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .unwrap();
+
+        Message::new(
+            "NotificationSetAllReadMutation",
+            json!({ "userId": user_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        let notification = sqlx::query!(
+            "SELECT seen FROM notification WHERE id = ?",
+            notification_id
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .unwrap();
+        assert_eq!(notification.seen, 0);
+    }
+
+    #[actix_rt::test]
+    async fn counts_the_notifications_returned_by_notifications_query() {
+        for user_id in [1, 10] {
+            let unread_notifications = Message::new(
+                "NotificationsQuery",
+                json!({ "userId": user_id, "unreadOnly": true }),
+            )
+            .execute()
+            .await
+            .get_json()["notifications"]
+                .as_array()
+                .unwrap()
+                .len();
+
+            Message::new("UnreadNotificationCountQuery", json!({ "userId": user_id }))
+                .execute()
+                .await
+                .should_be_ok_with(|result| {
+                    assert_eq!(result["unreadCount"], unread_notifications)
+                });
+        }
+    }
+}
+
+mod email_notifications {
+    use test_utils::{assert_eq, *};
+
+    /// Creates a user with a pending email notification which was created `days_ago` days ago.
+    async fn create_pending_email_notification(
+        days_ago: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> i32 {
+        let user_id = create_new_test_user(&mut **transaction).await.unwrap();
+
+        sqlx::query!(
+            "INSERT INTO subscription (uuid_id, user_id, notify_mailman) VALUES (1565, ?, 1)",
+            user_id
+        )
+        .execute(&mut **transaction)
+        .await
+        .unwrap();
+
+        Message::new(
+            "ThreadCreateThreadMutation",
+            json!({
+                "title": "Title",
+                "content": "This is content.",
+                "objectId": 1565,
+                "userId": 1,
+                "subscribe": false,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut *transaction)
+        .await
+        .should_be_ok();
+
+        sqlx::query!(
+            "UPDATE notification SET date = DATE_SUB(date, INTERVAL ? DAY) WHERE user_id = ?",
+            days_ago,
+            user_id
+        )
+        .execute(&mut **transaction)
+        .await
+        .unwrap();
+
+        user_id
+    }
+
+    fn find_recipient(result: &Value, user_id: i32) -> Option<Value> {
+        result["recipients"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|recipient| recipient["userId"] == user_id)
+            .cloned()
+    }
+
+    #[actix_rt::test]
+    async fn returns_pending_email_notifications_grouped_by_user() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_pending_email_notification(30, &mut transaction).await;
+
+        Message::new(
+            "PendingEmailNotificationsQuery",
+            json!({ "first": 10_000, "window": "daily" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            let recipient = find_recipient(&result, user_id).unwrap();
+            assert!(recipient["email"].is_string());
+            assert_eq!(recipient["instance"], "de");
+            assert_has_length(&recipient["notifications"], 1);
+            assert_json_include!(
+                actual: &recipient["notifications"][0]["event"],
+                expected: json!({
+                    "__typename": "CreateThreadNotificationEvent",
+                    "actorId": 1,
+                    "objectId": 1565,
+                })
+            );
+        });
+    }
+
+    #[actix_rt::test]
+    async fn digest_excludes_notifications_of_the_current_window() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_pending_email_notification(0, &mut transaction).await;
+
+        for window in ["daily", "weekly"] {
+            Message::new(
+                "PendingEmailNotificationsQuery",
+                json!({ "first": 10_000, "window": window }),
+            )
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert!(find_recipient(&result, user_id).is_none()));
+        }
+
+        Message::new("PendingEmailNotificationsQuery", json!({ "first": 10_000 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert!(find_recipient(&result, user_id).is_some()));
+    }
+
+    #[actix_rt::test]
+    async fn limits_the_number_of_recipients() {
+        let mut transaction = begin_transaction().await;
+        create_pending_email_notification(1, &mut transaction).await;
+
+        let all_recipients =
+            Message::new("PendingEmailNotificationsQuery", json!({ "first": 10_000 }))
+                .execute_on(&mut transaction)
+                .await
+                .get_json();
+
+        Message::new("PendingEmailNotificationsQuery", json!({ "first": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["recipients"], 1);
+                assert_eq!(result["recipients"][0], all_recipients["recipients"][0]);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn notifications_can_only_be_claimed_once() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_pending_email_notification(8, &mut transaction).await;
+
+        let result = Message::new(
+            "PendingEmailNotificationsQuery",
+            json!({ "first": 10_000, "window": "weekly" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json();
+        let notification_id =
+            find_recipient(&result, user_id).unwrap()["notifications"][0]["id"].clone();
+
+        Message::new(
+            "MarkNotificationsEmailedMutation",
+            json!({ "ids": [notification_id] }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "claimedIds": [notification_id] }));
+
+        Message::new(
+            "MarkNotificationsEmailedMutation",
+            json!({ "ids": [notification_id] }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "claimedIds": [] }));
+
+        Message::new("PendingEmailNotificationsQuery", json!({ "first": 10_000 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert!(find_recipient(&result, user_id).is_none()));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("PendingEmailNotificationsQuery", json!({ "first": 10_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
+
+mod notification_preferences {
+    use test_utils::{assert_eq, *};
+
+    async fn create_subscribed_user(transaction: &mut sqlx::Transaction<'_, sqlx::MySql>) -> i32 {
+        let user_id = create_new_test_user(&mut **transaction).await.unwrap();
+
+        sqlx::query!(
+            "INSERT INTO subscription (uuid_id, user_id, notify_mailman) VALUES (1565, ?, 1)",
+            user_id
+        )
+        .execute(&mut **transaction)
+        .await
+        .unwrap();
+
+        user_id
+    }
+
+    async fn set_preference(
+        user_id: i32,
+        event_type: &str,
+        delivery: Value,
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) {
+        Message::new(
+            "NotificationSetPreferenceMutation",
+            json!({ "userId": user_id, "eventType": event_type, "delivery": delivery }),
+        )
+        .execute_on(&mut *transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+    }
+
+    async fn create_thread(transaction: &mut sqlx::Transaction<'_, sqlx::MySql>) {
+        Message::new(
+            "ThreadCreateThreadMutation",
+            json!({
+                "title": "Title",
+                "content": "This is content.",
+                "objectId": 1565,
+                "userId": 1,
+                "subscribe": false,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut *transaction)
+        .await
+        .should_be_ok();
+    }
+
+    #[actix_rt::test]
+    async fn preferences_can_be_set_and_removed() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_new_test_user(&mut *transaction).await.unwrap();
+
+        set_preference(
+            user_id,
+            "CreateThreadNotificationEvent",
+            json!("inApp"),
+            &mut transaction,
+        )
+        .await;
+        set_preference(
+            user_id,
+            "CreateThreadNotificationEvent",
+            json!("inAppAndEmail"),
+            &mut transaction,
+        )
+        .await;
+        set_preference(
+            user_id,
+            "CreateEntityRevisionNotificationEvent",
+            json!("mute"),
+            &mut transaction,
+        )
+        .await;
+
+        Message::new("NotificationPreferencesQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({
+                "userId": user_id,
+                "preferences": [
+                    { "eventType": "CreateEntityRevisionNotificationEvent", "delivery": "mute" },
+                    { "eventType": "CreateThreadNotificationEvent", "delivery": "inAppAndEmail" },
+                ]
+            }));
+
+        set_preference(
+            user_id,
+            "CreateEntityRevisionNotificationEvent",
+            Value::Null,
+            &mut transaction,
+        )
+        .await;
+
+        Message::new("NotificationPreferencesQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert_has_length(&result["preferences"], 1));
+    }
+
+    #[actix_rt::test]
+    async fn muted_event_types_create_no_notifications() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_subscribed_user(&mut transaction).await;
+
+        set_preference(
+            user_id,
+            "CreateThreadNotificationEvent",
+            json!("mute"),
+            &mut transaction,
+        )
+        .await;
+        create_thread(&mut transaction).await;
+
+        Message::new("NotificationsQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert_has_length(&result["notifications"], 0));
+    }
+
+    #[actix_rt::test]
+    async fn in_app_preference_disables_emails() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_subscribed_user(&mut transaction).await;
+
+        set_preference(
+            user_id,
+            "CreateThreadNotificationEvent",
+            json!("inApp"),
+            &mut transaction,
+        )
+        .await;
+        create_thread(&mut transaction).await;
+
+        Message::new("NotificationsQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["notifications"], 1);
+                assert_eq!(result["notifications"][0]["email"], false);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn other_event_types_are_not_affected_by_preferences() {
+        let mut transaction = begin_transaction().await;
+        let user_id = create_subscribed_user(&mut transaction).await;
+
+        set_preference(
+            user_id,
+            "CreateCommentNotificationEvent",
+            json!("mute"),
+            &mut transaction,
+        )
+        .await;
+        create_thread(&mut transaction).await;
+
+        Message::new("NotificationsQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["notifications"], 1);
+                assert_eq!(result["notifications"][0]["email"], true);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_user_does_not_exist() {
+        Message::new(
+            "NotificationSetPreferenceMutation",
+            json!({
+                "userId": 1565,
+                "eventType": "CreateThreadNotificationEvent",
+                "delivery": "mute"
+            }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
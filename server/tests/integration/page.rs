@@ -59,16 +59,180 @@ mod create_mutation {
     }
 }
 
+mod set_metadata_mutation {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn sets_metadata_of_page() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "PageSetMetadataMutation",
+            json!({
+                "pageId": 23579,
+                "userId": 1,
+                "discussionsEnabled": true,
+                "forumId": null,
+                "licenseId": 1,
+                "instance": "de",
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("UuidQuery", json!({ "id": 23579 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["instance"], "de");
+                assert_eq!(result["licenseId"], 1);
+            });
+
+        Message::new("EventsQuery", json!({ "first": 1, "objectId": 23579 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_json_include!(
+                    actual: &result["events"][0],
+                    expected: json!({
+                        "__typename": "SetPageMetadataNotificationEvent",
+                        "instance": "de",
+                        "actorId": 1,
+                        "objectId": 23579,
+                        "pageId": 23579,
+                        "previousInstance": "de",
+                        "discussionsEnabled": true,
+                        "forumId": null,
+                    })
+                )
+            });
+    }
+
+    #[actix_rt::test]
+    async fn logs_license_change() {
+        let mut transaction = begin_transaction().await;
+        let license_id = Message::new("UuidQuery", json!({ "id": 16256 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["licenseId"]
+            .clone();
+        let new_license_id = if license_id == 1 { 2 } else { 1 };
+
+        Message::new(
+            "PageSetMetadataMutation",
+            json!({
+                "pageId": 16256,
+                "userId": 1,
+                "discussionsEnabled": false,
+                "forumId": null,
+                "licenseId": new_license_id,
+                "instance": "de",
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("EventsQuery", json!({ "first": 2, "objectId": 16256 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                let typenames: Vec<_> = result["events"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|event| event["__typename"].clone())
+                    .collect();
+                assert!(typenames.contains(&json!("SetLicenseNotificationEvent")));
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_page_does_not_exist() {
+        Message::new(
+            "PageSetMetadataMutation",
+            json!({
+                "pageId": 1,
+                "userId": 1,
+                "discussionsEnabled": false,
+                "forumId": null,
+                "licenseId": 1,
+                "instance": "de",
+            }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_user_does_not_exist() {
+        Message::new(
+            "PageSetMetadataMutation",
+            json!({
+                "pageId": 16256,
+                "userId": 999_999,
+                "discussionsEnabled": false,
+                "forumId": null,
+                "licenseId": 1,
+                "instance": "de",
+            }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_license_does_not_exist() {
+        Message::new(
+            "PageSetMetadataMutation",
+            json!({
+                "pageId": 16256,
+                "userId": 1,
+                "discussionsEnabled": false,
+                "forumId": null,
+                "licenseId": 999_999,
+                "instance": "de",
+            }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_forum_is_not_a_forum() {
+        Message::new(
+            "PageSetMetadataMutation",
+            json!({
+                "pageId": 16256,
+                "userId": 1,
+                "discussionsEnabled": true,
+                "forumId": 1394,
+                "licenseId": 1,
+                "instance": "de",
+            }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
+
 mod pages_query {
-    use test_utils::*;
+    use test_utils::{assert_eq, *};
 
     #[actix_rt::test]
     async fn fetches_all_pages() {
         Message::new("PagesQuery", json!({}))
             .execute()
             .await
-            .should_be_ok_with_body(json!({
-                "pages": [
+            .should_be_ok_with(|result| {
+                assert_json_include!(actual: result, expected: json!({
+                    "hasNextPage": false,
+                    "pages": [
                     16256,16303,16306,16530,16569,16659,16816,18233,18340,18778,18922,18998,19358,
                     19722,19723,19757,19763,19767,19808,19849,19852,19854,19856,19860,19863,19865,
                     19869,19871,19875,19880,19882,19973,19981,19991,19996,20003,20064,20076,20103,
@@ -80,8 +244,10 @@ mod pages_query {
                     25017,25019,25063,25079,25082,25294,25363,25373,25598,25713,25985,26087,26089,
                     26095,26245,26453,26473,26524,26542,26544,26546,26592,26633,26639,26874,26880,
                     27203,27421,27469,27472,31996,32840,32875,32966,35093,35096,35098,35100,35152
-                ]
-            }))
+                    ]
+                }));
+                assert_has_length(&result["pages"], 143);
+            });
     }
 
     #[actix_rt::test]
@@ -89,9 +255,14 @@ mod pages_query {
         Message::new("PagesQuery", json!({"instance": "en"}))
             .execute()
             .await
-            .should_be_ok_with_body(json!({
-                "pages": [23579, 23580, 23591, 23711, 23720, 23727, 25079, 25082, 27469, 32840, 32966]
-            }))
+            .should_be_ok_with(|result| {
+                assert_eq!(
+                    result["pages"],
+                    json!([
+                        23579, 23580, 23591, 23711, 23720, 23727, 25079, 25082, 27469, 32840, 32966
+                    ])
+                );
+            });
     }
 
     #[actix_rt::test]
@@ -99,6 +270,82 @@ mod pages_query {
         Message::new("PagesQuery", json!({"instance": "hi"}))
             .execute()
             .await
-            .should_be_ok_with_body(json!({ "pages": [] }))
+            .should_be_ok_with_body(json!({ "pages": [], "nodes": [], "hasNextPage": false }))
+    }
+
+    #[actix_rt::test]
+    async fn returns_title_and_alias_of_pages() {
+        Message::new("PagesQuery", json!({ "instance": "en", "first": 1 }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["pages"], json!([23579]));
+                assert_eq!(result["nodes"][0]["id"], 23579);
+                assert_eq!(result["nodes"][0]["trashed"], false);
+                let title = result["nodes"][0]["title"].as_str().unwrap();
+                assert_eq!(
+                    result["nodes"][0]["alias"],
+                    server::format_alias(None, 23579, Some(title))
+                );
+                assert_eq!(result["hasNextPage"], true);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn paginates_pages_with_after() {
+        Message::new(
+            "PagesQuery",
+            json!({ "instance": "en", "first": 3, "after": 23711 }),
+        )
+        .execute()
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["pages"], json!([23720, 23727, 25079]));
+            assert_eq!(result["hasNextPage"], true);
+        });
+    }
+
+    #[actix_rt::test]
+    async fn filters_trashed_pages() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "UuidSetStateMutation",
+            json!({ "ids": [23580], "userId": 1, "trashed": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("PagesQuery", json!({ "instance": "en", "trashed": true }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert_eq!(result["pages"], json!([23580])));
+
+        Message::new("PagesQuery", json!({ "instance": "en", "trashed": false }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(
+                    result["pages"],
+                    json!([23579, 23591, 23711, 23720, 23727, 25079, 25082, 27469, 32840, 32966])
+                );
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("PagesQuery", json!({ "first": 10_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_negative() {
+        Message::new("PagesQuery", json!({ "first": -1 }))
+            .execute()
+            .await
+            .should_be_bad_request();
     }
 }
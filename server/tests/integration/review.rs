@@ -0,0 +1,230 @@
+mod review_claim_mutation {
+    use test_utils::{assert_eq, *};
+
+    async fn fetch_pending_revision_id(
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> i32 {
+        Message::new("UnrevisedEntitiesQuery", json!({ "first": 1 }))
+            .execute_on(transaction)
+            .await
+            .get_json()["unrevisedEntities"][0]["oldestRevisionId"]
+            .as_i64()
+            .unwrap() as i32
+    }
+
+    #[actix_rt::test]
+    async fn claims_revision_and_lists_claim() {
+        let mut transaction = begin_transaction().await;
+        let revision_id = fetch_pending_revision_id(&mut transaction).await;
+
+        Message::new(
+            "ReviewClaimMutation",
+            json!({ "revisionId": revision_id, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["revisionId"], revision_id);
+            assert_eq!(result["repositoryId"], 26892);
+            assert_eq!(result["userId"], 1);
+        });
+
+        Message::new("ReviewClaimsQuery", json!({ "userId": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["claims"][0]["revisionId"], revision_id);
+            });
+
+        Message::new("UnrevisedEntitiesQuery", json!({ "first": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["unrevisedEntities"][0]["claims"][0]["userId"], 1);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_revision_is_claimed_by_other_reviewer() {
+        let mut transaction = begin_transaction().await;
+        let revision_id = fetch_pending_revision_id(&mut transaction).await;
+        let other_reviewer_id = create_new_test_user(&mut *transaction).await.unwrap();
+
+        Message::new(
+            "ReviewClaimMutation",
+            json!({ "revisionId": revision_id, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "ReviewClaimMutation",
+            json!({ "revisionId": revision_id, "userId": other_reviewer_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+
+        Message::new(
+            "ReviewReleaseClaimMutation",
+            json!({ "revisionId": revision_id, "userId": other_reviewer_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_revision_is_not_pending() {
+        Message::new(
+            "ReviewClaimMutation",
+            json!({ "revisionId": 1, "userId": 1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn checkout_is_refused_unless_forced_when_claimed_by_other_reviewer() {
+        let mut transaction = begin_transaction().await;
+        let revision_id = fetch_pending_revision_id(&mut transaction).await;
+        let other_reviewer_id = create_new_test_user(&mut *transaction).await.unwrap();
+
+        Message::new(
+            "ReviewClaimMutation",
+            json!({ "revisionId": revision_id, "userId": other_reviewer_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "EntityCheckoutRevisionMutation",
+            json!({ "revisionId": revision_id, "userId": 1, "reason": "" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+
+        Message::new(
+            "EntityCheckoutRevisionMutation",
+            json!({ "revisionId": revision_id, "userId": 1, "reason": "", "force": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("ReviewClaimsQuery", json!({ "userId": other_reviewer_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "claims": [] }));
+    }
+
+    #[actix_rt::test]
+    async fn page_review_is_refused_unless_forced_when_claimed_by_other_reviewer() {
+        let mut transaction = begin_transaction().await;
+        let other_reviewer_id = create_new_test_user(&mut *transaction).await.unwrap();
+
+        sqlx::query!("INSERT INTO uuid (trashed, discriminator) VALUES (0, 'pageRevision')")
+            .execute(&mut *transaction)
+            .await
+            .unwrap();
+        let revision_id = sqlx::query!("SELECT LAST_INSERT_ID() as id")
+            .fetch_one(&mut *transaction)
+            .await
+            .unwrap()
+            .id as i32;
+        sqlx::query!(
+            r#"
+                INSERT INTO page_revision (id, author_id, page_repository_id, title, content, date)
+                    VALUES (?, 1, 16256, "Title", "Content", NOW())
+            "#,
+            revision_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .unwrap();
+
+        Message::new(
+            "ReviewClaimMutation",
+            json!({ "revisionId": revision_id, "userId": other_reviewer_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| assert_eq!(result["repositoryId"], 16256));
+
+        for message in ["PageRejectRevisionMutation", "PageCheckoutRevisionMutation"] {
+            Message::new(
+                message,
+                json!({ "revisionId": revision_id, "userId": 1, "reason": "" }),
+            )
+            .execute_on(&mut transaction)
+            .await
+            .should_be_bad_request();
+        }
+
+        Message::new(
+            "PageCheckoutRevisionMutation",
+            json!({ "revisionId": revision_id, "userId": 1, "reason": "", "force": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("ReviewClaimsQuery", json!({ "userId": other_reviewer_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "claims": [] }));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_user_does_not_exist() {
+        let mut transaction = begin_transaction().await;
+        let revision_id = fetch_pending_revision_id(&mut transaction).await;
+
+        Message::new(
+            "ReviewClaimMutation",
+            json!({ "revisionId": revision_id, "userId": 1565 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+}
+
+mod review_release_claim_mutation {
+    use test_utils::*;
+
+    #[actix_rt::test]
+    async fn releases_claim() {
+        let mut transaction = begin_transaction().await;
+        let revision_id = Message::new("UnrevisedEntitiesQuery", json!({ "first": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["unrevisedEntities"][0]["oldestRevisionId"]
+            .clone();
+
+        Message::new(
+            "ReviewClaimMutation",
+            json!({ "revisionId": revision_id, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "ReviewReleaseClaimMutation",
+            json!({ "revisionId": revision_id, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("ReviewClaimsQuery", json!({ "userId": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "claims": [] }));
+    }
+}
@@ -0,0 +1,123 @@
+mod search_query {
+    use server::create_database_pool;
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn returns_ranked_results_with_snippets() {
+        // InnoDB only updates FULLTEXT indexes on commit, so the entries cannot be created inside
+        // of a transaction which is rolled back.
+        let pool = create_database_pool().await.unwrap();
+
+        for (id, title, text) in [
+            (
+                16256,
+                "Zwiebelkuchen",
+                "Ein Rezept für Zwiebelkuchen mit viel Zwiebelkuchen",
+            ),
+            (
+                23579,
+                "Rezepte",
+                "Hier steht auch etwas über Zwiebelkuchen.",
+            ),
+        ] {
+            sqlx::query!(
+                r#"
+                    INSERT INTO search_index
+                        (uuid_id, instance_id, typename, title, meta_description, text)
+                        SELECT ?, id, "Page", ?, "", ? FROM instance WHERE subdomain = "en"
+                        ON DUPLICATE KEY UPDATE title = VALUES(title), text = VALUES(text)
+                "#,
+                id,
+                title,
+                text
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let result = Message::new(
+            "SearchQuery",
+            json!({ "query": "Zwiebelkuchen", "instance": "en", "first": 10 }),
+        )
+        .execute()
+        .await
+        .get_json();
+
+        sqlx::query!("DELETE FROM search_index WHERE uuid_id IN (16256, 23579)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(result["results"][0]["id"], 16256);
+        assert_eq!(result["results"][1]["id"], 23579);
+        assert_eq!(result["results"][0]["__typename"], "Page");
+        assert!(result["results"][1]["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("<mark>Zwiebelkuchen</mark>"));
+        assert_eq!(result["hasNextPage"], false);
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_query_is_empty() {
+        Message::new("SearchQuery", json!({ "query": " ", "first": 10 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new(
+            "SearchQuery",
+            json!({ "query": "Ableitung", "first": 10_001 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_negative() {
+        Message::new("SearchQuery", json!({ "query": "Ableitung", "first": -1 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
+
+mod search_index_rebuild_mutation {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn indexes_entities_and_pages_in_batches() {
+        let mut transaction = begin_transaction().await;
+
+        let first_batch = Message::new("SearchIndexRebuildMutation", json!({ "first": 2 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json();
+
+        assert_has_length(&first_batch["indexedIds"], 2);
+        assert_eq!(first_batch["hasNextPage"], true);
+
+        Message::new(
+            "SearchIndexRebuildMutation",
+            json!({ "first": 2, "after": first_batch["indexedIds"][1] }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert!(result["indexedIds"][0].as_i64() > first_batch["indexedIds"][1].as_i64());
+        });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("SearchIndexRebuildMutation", json!({ "first": 1_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
@@ -1,54 +1,283 @@
 mod subjects_query {
-    use test_utils::*;
+    use test_utils::{assert_eq, *};
 
     #[actix_rt::test]
     async fn returns_list_of_subjects() {
         Message::new("SubjectsQuery", Value::Null)
             .execute()
             .await
-            .should_be_ok_with_body(json!({
-              "subjects": [
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 5
-                },
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 17744
-                },
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 18230
-                },
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 23362
-                },
-                {
-                  "instance": "en",
-                  "taxonomyTermId": 23593
-                },
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 25712
-                },
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 25979
-                },
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 26523
-                },
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 33894
+            .should_be_ok_with(|result| {
+                let subjects: Vec<(String, i64)> = result["subjects"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|subject| {
+                        (
+                            subject["instance"].as_str().unwrap().to_string(),
+                            subject["taxonomyTermId"].as_i64().unwrap(),
+                        )
+                    })
+                    .collect();
+
+                assert_eq!(
+                    subjects,
+                    vec![
+                        ("de", 5),
+                        ("de", 17744),
+                        ("de", 18230),
+                        ("de", 23362),
+                        ("en", 23593),
+                        ("de", 25712),
+                        ("de", 25979),
+                        ("de", 26523),
+                        ("de", 33894),
+                        ("de", 35608)
+                    ]
+                    .into_iter()
+                    .map(|(instance, id)| (instance.to_string(), id))
+                    .collect::<Vec<_>>()
+                );
+            });
+    }
+
+    #[actix_rt::test]
+    async fn returns_names_aliases_and_counts() {
+        Message::new("SubjectsQuery", json!({ "instance": "en" }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                let subjects = result["subjects"].as_array().unwrap();
+                assert!(subjects.iter().all(|subject| subject["instance"] == "en"));
+
+                let subject = subjects
+                    .iter()
+                    .find(|subject| subject["taxonomyTermId"] == 23593)
+                    .unwrap();
+                let name = subject["name"].as_str().unwrap();
+
+                assert!(!name.is_empty());
+                assert_eq!(
+                    subject["alias"],
+                    format!("/{0}/23593/{0}", name.to_lowercase().replace(' ', "-"))
+                );
+                assert!(subject["entityCounts"].is_object());
+                assert!(subject["unrevisedRevisionCount"].as_i64().unwrap() >= 0);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn counts_unrevised_revisions() {
+        let mut transaction = begin_transaction().await;
+
+        let subject_id = Message::new("UuidQuery", json!({ "id": 1503 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["canonicalSubjectId"]
+            .clone();
+
+        let count_before = Message::new("SubjectsQuery", json!({ "instance": "de" }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["subjects"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|subject| subject["taxonomyTermId"] == subject_id)
+            .unwrap()["unrevisedRevisionCount"]
+            .as_i64()
+            .unwrap();
+
+        Message::new(
+            "EntityAddRevisionMutation",
+            json!({
+                "revisionType": "ArticleRevision",
+                "input": {
+                    "changes": "test changes",
+                    "entityId": 1503,
+                    "needsReview": true,
+                    "subscribeThis": false,
+                    "subscribeThisByEmail": false,
+                    "fields": {
+                        "content": "test content",
+                        "title": "test title",
+                        "metaTitle": "test metaTitle",
+                        "metaDescription": "test metaDescription"
+                    }
                 },
-                {
-                  "instance": "de",
-                  "taxonomyTermId": 35608
-                }
-              ]
-            }));
+                "userId": 1
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("SubjectsQuery", json!({ "instance": "de" }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                let subject = result["subjects"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .find(|subject| subject["taxonomyTermId"] == subject_id)
+                    .unwrap()
+                    .clone();
+
+                assert_eq!(
+                    subject["unrevisedRevisionCount"].as_i64().unwrap(),
+                    count_before + 1
+                );
+            });
+    }
+}
+
+mod subject_registry {
+    use test_utils::{assert_eq, *};
+
+    async fn create_topic(
+        parent_id: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> i32 {
+        Message::new(
+            "TaxonomyTermCreateMutation",
+            json!({
+                "parentId": parent_id,
+                "name": "a topic",
+                "description": null,
+                "userId": 1,
+                "taxonomyType": "topic"
+            }),
+        )
+        .execute_on(transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap() as i32
+    }
+
+    #[actix_rt::test]
+    async fn sets_queries_and_removes_entries() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "SubjectRegistrySetMutation",
+            json!({
+                "taxonomyTermId": 1394,
+                "kind": "subject",
+                "ambSubjects": ["http://w3id.org/kim/schulfaecher/s1017"],
+                "thumbnail": "mathe.png"
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("SubjectRegistryQuery", json!({ "kind": "subject" }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                let entry = result["entries"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .find(|entry| entry["taxonomyTermId"] == 1394)
+                    .unwrap()
+                    .clone();
+
+                assert_eq!(
+                    entry,
+                    json!({
+                        "taxonomyTermId": 1394,
+                        "kind": "subject",
+                        "ambSubjects": ["http://w3id.org/kim/schulfaecher/s1017"],
+                        "thumbnail": "mathe.png"
+                    })
+                );
+            });
+
+        Message::new(
+            "SubjectRegistryRemoveMutation",
+            json!({ "taxonomyTermId": 1394 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("SubjectRegistryQuery", json!({ "kind": null }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert!(!result["entries"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|entry| entry["taxonomyTermId"] == 1394));
+            });
+    }
+
+    #[actix_rt::test]
+    async fn children_of_containers_in_construction_are_subjects() {
+        let mut transaction = begin_transaction().await;
+        let container = create_topic(1394, &mut transaction).await;
+        let subject = create_topic(container, &mut transaction).await;
+
+        Message::new(
+            "SubjectRegistrySetMutation",
+            json!({ "taxonomyTermId": container, "kind": "in-construction" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("SubjectsQuery", Value::Null)
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert!(result["subjects"]
+                    .as_array()
+                    .unwrap()
+                    .contains(&json!({ "instance": "de", "taxonomyTermId": subject })));
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_amb_subject_is_unknown() {
+        Message::new(
+            "SubjectRegistrySetMutation",
+            json!({
+                "taxonomyTermId": 1394,
+                "kind": "subject",
+                "ambSubjects": ["https://example.org/mathematics"]
+            }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_containers_have_metadata() {
+        Message::new(
+            "SubjectRegistrySetMutation",
+            json!({
+                "taxonomyTermId": 1394,
+                "kind": "excluded",
+                "thumbnail": "mathe.png"
+            }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_entry_does_not_exist() {
+        Message::new(
+            "SubjectRegistryRemoveMutation",
+            json!({ "taxonomyTermId": 1394 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
     }
 }
@@ -148,6 +148,42 @@ mod create_mutation {
             });
     }
 
+    #[actix_rt::test]
+    async fn fails_with_bad_request_if_parent_does_not_allow_type() {
+        let mut transaction = begin_transaction().await;
+
+        let folder_id = Message::new(
+            "TaxonomyTermCreateMutation",
+            json! ({
+            "parentId": 1394,
+            "name": "a folder",
+            "description": null,
+            "userId": 1,
+            "taxonomyType": "topic-folder"
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json()["id"]
+            .clone();
+
+        for taxonomy_type in ["topic", "topic-folder", "subject"] {
+            Message::new(
+                "TaxonomyTermCreateMutation",
+                json! ({
+                "parentId": folder_id,
+                "name": "a name",
+                "description": null,
+                "userId": 1,
+                "taxonomyType": taxonomy_type
+                }),
+            )
+            .execute_on(&mut transaction)
+            .await
+            .should_be_bad_request();
+        }
+    }
+
     #[actix_rt::test]
     async fn fails_with_bad_request_if_parent_does_not_exist() {
         Message::new(
@@ -542,3 +578,496 @@ mod sort_mutation {
         .should_be_bad_request();
     }
 }
+
+mod move_mutation {
+    use test_utils::{assert_eq, *};
+
+    async fn create_term(
+        taxonomy_type: &str,
+        parent_id: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> i32 {
+        Message::new(
+            "TaxonomyTermCreateMutation",
+            json!({
+                "parentId": parent_id,
+                "name": format!("a {taxonomy_type} below {parent_id}"),
+                "description": null,
+                "userId": 1,
+                "taxonomyType": taxonomy_type
+            }),
+        )
+        .execute_on(transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap() as i32
+    }
+
+    #[actix_rt::test]
+    async fn moves_terms_to_the_end_of_the_new_parent() {
+        let mut transaction = begin_transaction().await;
+        let destination = create_term("topic", 1394, &mut transaction).await;
+        let first = create_term("topic", 1394, &mut transaction).await;
+        let second = create_term("topic-folder", 1394, &mut transaction).await;
+
+        Message::new(
+            "TaxonomyTermMoveMutation",
+            json!({ "ids": [first, second], "destination": destination, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("UuidQuery", json!({ "id": destination }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["childrenIds"], json!([first, second]));
+            });
+
+        Message::new("EventsQuery", json!({ "first": 1, "objectId": second }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_json_include!(
+                    actual: &result["events"][0],
+                    expected: json!({
+                        "__typename": "SetTaxonomyParentNotificationEvent",
+                        "actorId": 1,
+                        "childId": second,
+                        "previousParentId": 1394,
+                        "parentId": destination
+                    })
+                );
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_move_would_create_a_cycle() {
+        let mut transaction = begin_transaction().await;
+        let parent = create_term("topic", 1394, &mut transaction).await;
+        let child = create_term("topic", parent, &mut transaction).await;
+
+        Message::new(
+            "TaxonomyTermMoveMutation",
+            json!({ "ids": [parent], "destination": child, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_parent_does_not_allow_type() {
+        let mut transaction = begin_transaction().await;
+        let folder = create_term("topic-folder", 1394, &mut transaction).await;
+        let topic = create_term("topic", 1394, &mut transaction).await;
+
+        Message::new(
+            "TaxonomyTermMoveMutation",
+            json!({ "ids": [topic], "destination": folder, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_destination_is_in_another_instance() {
+        let mut transaction = begin_transaction().await;
+        let topic = create_term("topic", 1394, &mut transaction).await;
+
+        Message::new(
+            "TaxonomyTermMoveMutation",
+            json!({ "ids": [topic], "destination": 23593, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_destination_does_not_exist() {
+        Message::new(
+            "TaxonomyTermMoveMutation",
+            json!({ "ids": [1394], "destination": 1, "userId": 1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
+
+mod merge_mutation {
+    use test_utils::{assert_eq, *};
+
+    async fn create_term(
+        taxonomy_type: &str,
+        parent_id: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> i32 {
+        Message::new(
+            "TaxonomyTermCreateMutation",
+            json!({
+                "parentId": parent_id,
+                "name": format!("a {taxonomy_type} below {parent_id}"),
+                "description": null,
+                "userId": 1,
+                "taxonomyType": taxonomy_type
+            }),
+        )
+        .execute_on(transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap() as i32
+    }
+
+    #[actix_rt::test]
+    async fn merges_source_into_target() {
+        let mut transaction = begin_transaction().await;
+        let target = create_term("topic", 1394, &mut transaction).await;
+        let source = create_term("topic", 1394, &mut transaction).await;
+        let child = create_term("topic", source, &mut transaction).await;
+
+        Message::new(
+            "TaxonomyCreateEntityLinksMutation",
+            json!({ "userId": 1, "entityIds": [1495], "taxonomyTermId": source }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "TaxonomyTermMergeMutation",
+            json!({ "sourceId": source, "targetId": target, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("UuidQuery", json!({ "id": target }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["childrenIds"], json!([1495, child]));
+            });
+
+        Message::new("UuidQuery", json!({ "id": source }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["trashed"], true);
+                assert_eq!(result["childrenIds"], json!([]));
+            });
+
+        Message::new("EventsQuery", json!({ "first": 1, "objectId": child }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_json_include!(
+                    actual: &result["events"][0],
+                    expected: json!({
+                        "__typename": "SetTaxonomyParentNotificationEvent",
+                        "childId": child,
+                        "previousParentId": source,
+                        "parentId": target
+                    })
+                );
+            });
+
+        Message::new("EventsQuery", json!({ "first": 1, "objectId": source }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_json_include!(
+                    actual: &result["events"][0],
+                    expected: json!({
+                        "__typename": "SetUuidStateNotificationEvent",
+                        "objectId": source,
+                        "trashed": true
+                    })
+                );
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_source_and_target_are_equal() {
+        Message::new(
+            "TaxonomyTermMergeMutation",
+            json!({ "sourceId": 1394, "targetId": 1394, "userId": 1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_target_is_a_descendant_of_source() {
+        let mut transaction = begin_transaction().await;
+        let source = create_term("topic", 1394, &mut transaction).await;
+        let target = create_term("topic", source, &mut transaction).await;
+
+        Message::new(
+            "TaxonomyTermMergeMutation",
+            json!({ "sourceId": source, "targetId": target, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_target_is_trashed() {
+        let mut transaction = begin_transaction().await;
+        let target = create_term("topic", 1394, &mut transaction).await;
+        let source = create_term("topic", 1394, &mut transaction).await;
+
+        Message::new(
+            "UuidSetStateMutation",
+            json!({ "ids": [target], "userId": 1, "trashed": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "TaxonomyTermMergeMutation",
+            json!({ "sourceId": source, "targetId": target, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_target_is_in_another_instance() {
+        let mut transaction = begin_transaction().await;
+        let source = create_term("topic", 1394, &mut transaction).await;
+
+        Message::new(
+            "TaxonomyTermMergeMutation",
+            json!({ "sourceId": source, "targetId": 23593, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_bad_request();
+    }
+}
+
+mod invalid_taxonomy_terms_query {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn lists_terms_violating_the_hierarchy() {
+        let mut transaction = begin_transaction().await;
+
+        let folder_id = Message::new(
+            "TaxonomyTermCreateMutation",
+            json!({
+                "parentId": 1394,
+                "name": "a folder",
+                "description": null,
+                "userId": 1,
+                "taxonomyType": "topic-folder"
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap();
+
+        let topic_id = Message::new(
+            "TaxonomyTermCreateMutation",
+            json!({
+                "parentId": 1394,
+                "name": "a topic",
+                "description": null,
+                "userId": 1,
+                "taxonomyType": "topic"
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap();
+
+        sqlx::query!(
+            "UPDATE term_taxonomy SET parent_id = ? WHERE id = ?",
+            folder_id,
+            topic_id
+        )
+        .execute(&mut *transaction)
+        .await
+        .unwrap();
+
+        Message::new(
+            "InvalidTaxonomyTermsQuery",
+            json!({ "first": 1, "after": topic_id - 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({
+            "terms": [{
+                "id": topic_id,
+                "type": "topic",
+                "parentId": folder_id,
+                "parentType": "topicFolder",
+                "reason": "type topic is not allowed below type topic-folder"
+            }],
+            "hasNextPage": false
+        }));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("InvalidTaxonomyTermsQuery", json!({ "first": 10_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
+
+mod taxonomy_tree_query {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn returns_nested_terms_with_entity_counts() {
+        let mut transaction = begin_transaction().await;
+
+        let topic_id = Message::new(
+            "TaxonomyTermCreateMutation",
+            json!({
+                "parentId": 1394,
+                "name": "a topic",
+                "description": null,
+                "userId": 1,
+                "taxonomyType": "topic"
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json()["id"]
+            .clone();
+
+        Message::new(
+            "TaxonomyCreateEntityLinksMutation",
+            json!({ "userId": 1, "entityIds": [1495], "taxonomyTermId": topic_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        let root = Message::new("TaxonomyTreeQuery", json!({ "rootId": 1394 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json();
+
+        assert_eq!(root["id"], 1394);
+
+        let topic = root["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|child| child["id"] == topic_id)
+            .unwrap()
+            .clone();
+
+        assert_eq!(topic["type"], "topic");
+        assert_eq!(topic["directEntityCounts"], json!({ "Article": 1 }));
+        assert_eq!(topic["transitiveEntityCounts"], json!({ "Article": 1 }));
+        assert!(
+            root["transitiveEntityCounts"]["Article"].as_i64()
+                > root["directEntityCounts"]["Article"].as_i64()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn counts_each_entity_of_the_whole_subtree_once() {
+        let mut transaction = begin_transaction().await;
+
+        let mut parent_id = json!(1394);
+        let mut topic_ids = Vec::new();
+
+        for name in ["a topic", "a subtopic"] {
+            let topic_id = Message::new(
+                "TaxonomyTermCreateMutation",
+                json!({
+                    "parentId": parent_id,
+                    "name": name,
+                    "description": null,
+                    "userId": 1,
+                    "taxonomyType": "topic"
+                }),
+            )
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["id"]
+                .clone();
+
+            Message::new(
+                "TaxonomyCreateEntityLinksMutation",
+                json!({ "userId": 1, "entityIds": [1495], "taxonomyTermId": topic_id }),
+            )
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok();
+
+            parent_id = topic_id.clone();
+            topic_ids.push(topic_id);
+        }
+
+        let topic = Message::new(
+            "TaxonomyTreeQuery",
+            json!({ "rootId": topic_ids[0], "maxDepth": 0 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json();
+
+        assert_eq!(topic["children"], json!([]));
+        assert_eq!(topic["directEntityCounts"], json!({ "Article": 1 }));
+        assert_eq!(topic["transitiveEntityCounts"], json!({ "Article": 1 }));
+
+        let shallow_root = Message::new(
+            "TaxonomyTreeQuery",
+            json!({ "rootId": 1394, "maxDepth": 0 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json();
+        let deep_root = Message::new("TaxonomyTreeQuery", json!({ "rootId": 1394 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json();
+
+        assert_eq!(
+            shallow_root["transitiveEntityCounts"],
+            deep_root["transitiveEntityCounts"]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn respects_max_depth() {
+        Message::new(
+            "TaxonomyTreeQuery",
+            json!({ "rootId": 1394, "maxDepth": 0 }),
+        )
+        .execute()
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["children"], json!([]));
+        });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_root_does_not_exist() {
+        Message::new("TaxonomyTreeQuery", json!({ "rootId": 1 }))
+            .execute()
+            .await
+            .should_be_not_found();
+    }
+}
@@ -156,10 +156,11 @@ mod thread_mutations {
             .should_be_ok_with(|comment| {
                 if content_should_change {
                     assert_ne!(comment["content"], original_comment["content"]);
-                    // assert_ne!(comment["edit_date"], original_comment["edit_date"]);
+                    assert_eq!(comment["edited"], true);
+                    assert_ne!(comment["lastEditedAt"], original_comment["lastEditedAt"]);
                 } else {
                     assert_eq!(comment["content"], original_comment["content"]);
-                    // assert_eq!(comment["edit_date"], original_comment["edit_date"]);
+                    assert_eq!(comment["lastEditedAt"], original_comment["lastEditedAt"]);
                 }
             });
     }
@@ -262,3 +263,566 @@ mod thread_mutations {
         }
     }
 }
+
+mod all_threads_query {
+    use test_utils::{assert_eq, *};
+
+    async fn create_thread(
+        object_id: i32,
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> i32 {
+        Message::new(
+            "ThreadCreateThreadMutation",
+            json!({
+                "title": "Title",
+                "content": "This is content.",
+                "objectId": object_id,
+                "userId": 1,
+                "subscribe": false,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut *transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap() as i32
+    }
+
+    #[actix_rt::test]
+    async fn sorts_threads_by_last_activity() {
+        let mut transaction = begin_transaction().await;
+
+        let thread_id = create_thread(1565, &mut transaction).await;
+
+        Message::new("AllThreadsQuery", json!({ "first": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "firstCommentIds": [thread_id], "hasNextPage": true }));
+
+        Message::new(
+            "ThreadCreateCommentMutation",
+            json!({
+                "threadId": 17774,
+                "userId": 1,
+                "content": "This is content.",
+                "subscribe": false,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("AllThreadsQuery", json!({ "first": 2 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                // 17774 and the new thread may have the same last activity
+                let ids = result["firstCommentIds"].as_array().unwrap();
+                assert!(ids.contains(&json!(17774)));
+                assert!(ids.contains(&json!(thread_id)));
+            });
+    }
+
+    #[actix_rt::test]
+    async fn paginates_threads_with_cursor() {
+        let mut transaction = begin_transaction().await;
+
+        let first_page = Message::new("AllThreadsQuery", json!({ "first": 4 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["firstCommentIds"]
+            .clone();
+        assert_has_length(&first_page, 4);
+
+        Message::new(
+            "AllThreadsQuery",
+            json!({ "first": 2, "after": first_page[1] }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(
+                result["firstCommentIds"],
+                json!([first_page[2], first_page[3]])
+            );
+            assert_eq!(result["hasNextPage"], true);
+        });
+    }
+
+    #[actix_rt::test]
+    async fn filters_threads_of_object_by_replies_and_status() {
+        let mut transaction = begin_transaction().await;
+
+        let thread_id = create_thread(1565, &mut transaction).await;
+
+        Message::new(
+            "AllThreadsQuery",
+            json!({ "first": 10, "objectId": 1565, "hasReplies": false, "status": "noStatus" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["firstCommentIds"][0], thread_id);
+        });
+
+        Message::new(
+            "AllThreadsQuery",
+            json!({ "first": 10, "objectId": 1565, "hasReplies": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            let ids = result["firstCommentIds"].as_array().unwrap();
+            assert!(!ids.contains(&json!(thread_id)));
+        });
+
+        Message::new(
+            "ThreadSetThreadStatusMutation",
+            json!({ "ids": [thread_id], "status": "done" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new(
+            "AllThreadsQuery",
+            json!({ "first": 10, "objectId": 1565, "status": "done" }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["firstCommentIds"], json!([thread_id]));
+        });
+    }
+
+    #[actix_rt::test]
+    async fn filters_threads_by_instance_and_archived_state() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new(
+            "ThreadSetThreadArchivedMutation",
+            json!({ "ids": [17774], "userId": 1, "archived": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        for (instance, archived, should_contain) in [
+            ("de", true, true),
+            ("de", false, false),
+            ("en", true, false),
+        ] {
+            Message::new(
+                "AllThreadsQuery",
+                json!({ "first": 10_000, "instance": instance, "archived": archived }),
+            )
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                let ids = result["firstCommentIds"].as_array().unwrap();
+                assert_eq!(ids.contains(&json!(17774)), should_contain);
+            });
+        }
+    }
+
+    #[actix_rt::test]
+    async fn filters_threads_by_subject() {
+        let mut transaction = begin_transaction().await;
+
+        let subject_id = Message::new("UuidQuery", json!({ "id": 1503 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["canonicalSubjectId"]
+            .clone();
+        let thread_id = create_thread(1503, &mut transaction).await;
+
+        Message::new(
+            "AllThreadsQuery",
+            json!({ "first": 1, "subjectId": subject_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_eq!(result["firstCommentIds"], json!([thread_id]));
+        });
+
+        Message::new(
+            "AllThreadsQuery",
+            json!({ "first": 10_000, "subjectId": 23593 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            let ids = result["firstCommentIds"].as_array().unwrap();
+            assert!(!ids.contains(&json!(thread_id)));
+        });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_first_is_too_high() {
+        Message::new("AllThreadsQuery", json!({ "first": 10_001 }))
+            .execute()
+            .await
+            .should_be_bad_request();
+    }
+}
+
+mod comment_history_query {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn stores_prior_versions_of_comment() {
+        let mut transaction = begin_transaction().await;
+
+        Message::new("CommentHistoryQuery", json!({ "id": 15469 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "history": [] }));
+
+        for content in ["first edit", "second edit"] {
+            Message::new(
+                "ThreadEditCommentMutation",
+                json!({ "userId": 2, "commentId": 15469, "content": content }),
+            )
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok();
+        }
+
+        Message::new("CommentHistoryQuery", json!({ "id": 15469 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["history"], 2);
+                assert_eq!(result["history"][0]["content"], "first edit");
+                assert_eq!(result["history"][1]["content"], "Bitte neu einsortieren :)");
+                assert_eq!(result["history"][0]["editorId"], 2);
+            });
+
+        Message::new("UuidQuery", json!({ "id": 15469 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["content"], "second edit");
+                assert_eq!(result["edited"], true);
+                assert!(result["lastEditedAt"].is_string());
+            });
+    }
+
+    #[actix_rt::test]
+    async fn unedited_comment_is_not_marked_as_edited() {
+        Message::new("UuidQuery", json!({ "id": 17666 }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                assert_eq!(result["edited"], false);
+                assert_eq!(result["lastEditedAt"], Value::Null);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_comment_does_not_exist() {
+        Message::new("CommentHistoryQuery", json!({ "id": 1 }))
+            .execute()
+            .await
+            .should_be_not_found();
+    }
+}
+
+mod thread_move_mutation {
+    use test_utils::{assert_eq, *};
+
+    #[actix_rt::test]
+    async fn moves_thread_to_new_object() {
+        let mut transaction = begin_transaction().await;
+
+        let previous_object_id = Message::new("UuidQuery", json!({ "id": 17774 }))
+            .execute_on(&mut transaction)
+            .await
+            .get_json()["parentId"]
+            .clone();
+
+        Message::new(
+            "ThreadMoveMutation",
+            json!({ "threadId": 17774, "newObjectId": 1565, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with_body(json!({ "success": true }));
+
+        Message::new("UuidQuery", json!({ "id": 17774 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert_eq!(result["parentId"], 1565));
+
+        Message::new("ThreadsQuery", json!({ "id": 1565 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                let ids = result["firstCommentIds"].as_array().unwrap();
+                assert!(ids.contains(&json!(17774)));
+            });
+
+        Message::new(
+            "EventsQuery",
+            json!({ "first": 1, "objectId": previous_object_id }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok_with(|result| {
+            assert_json_include!(
+                actual: &result["events"][0],
+                expected: json!({
+                    "__typename": "MoveThreadNotificationEvent",
+                    "actorId": 1,
+                    "objectId": 17774,
+                    "threadId": 17774,
+                    "previousObjectId": previous_object_id,
+                })
+            )
+        });
+    }
+
+    #[actix_rt::test]
+    async fn subscribers_of_thread_and_previous_object_follow_new_object() {
+        let mut transaction = begin_transaction().await;
+
+        let previous_object_id = sqlx::query!("SELECT uuid_id FROM comment WHERE id = 17774")
+            .fetch_one(&mut *transaction)
+            .await
+            .unwrap()
+            .uuid_id;
+
+        sqlx::query!("DELETE FROM subscription WHERE uuid_id = 1565 AND user_id = 10")
+            .execute(&mut *transaction)
+            .await
+            .unwrap();
+
+        for object_id in [previous_object_id, Some(17774)] {
+            sqlx::query!(
+                r#"
+                    INSERT INTO subscription (uuid_id, user_id, notify_mailman, date)
+                        VALUES (?, 10, 1, NOW())
+                    ON DUPLICATE KEY UPDATE notify_mailman = 1
+                "#,
+                object_id
+            )
+            .execute(&mut *transaction)
+            .await
+            .unwrap();
+        }
+
+        Message::new(
+            "ThreadMoveMutation",
+            json!({ "threadId": 17774, "newObjectId": 1565, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        let subscription = sqlx::query!(
+            "SELECT notify_mailman FROM subscription WHERE uuid_id = 1565 AND user_id = 10"
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .unwrap();
+        assert_eq!(subscription.notify_mailman, 1);
+    }
+
+    #[actix_rt::test]
+    async fn keeps_instance_when_thread_is_moved_to_a_user() {
+        let mut transaction = begin_transaction().await;
+
+        let instance_id = sqlx::query!("SELECT instance_id FROM comment WHERE id = 17774")
+            .fetch_one(&mut *transaction)
+            .await
+            .unwrap()
+            .instance_id;
+
+        Message::new(
+            "ThreadMoveMutation",
+            json!({ "threadId": 17774, "newObjectId": 10, "userId": 1 }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("UuidQuery", json!({ "id": 17774 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| assert_eq!(result["parentId"], 10));
+
+        let thread = sqlx::query!("SELECT instance_id FROM comment WHERE id = 17774")
+            .fetch_one(&mut *transaction)
+            .await
+            .unwrap();
+        assert_eq!(thread.instance_id, instance_id);
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_user_does_not_exist() {
+        Message::new(
+            "ThreadMoveMutation",
+            json!({ "threadId": 17774, "newObjectId": 1565, "userId": 999_999 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_thread_does_not_exist() {
+        Message::new(
+            "ThreadMoveMutation",
+            json!({ "threadId": 1, "newObjectId": 1565, "userId": 1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_new_object_is_a_comment() {
+        Message::new(
+            "ThreadMoveMutation",
+            json!({ "threadId": 17774, "newObjectId": 17666, "userId": 1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_new_object_is_the_current_object() {
+        let object_id = Message::new("UuidQuery", json!({ "id": 17774 }))
+            .execute()
+            .await
+            .get_json()["parentId"]
+            .clone();
+
+        Message::new(
+            "ThreadMoveMutation",
+            json!({ "threadId": 17774, "newObjectId": object_id, "userId": 1 }),
+        )
+        .execute()
+        .await
+        .should_be_bad_request();
+    }
+}
+
+mod mentions {
+    use test_utils::{assert_eq, *};
+
+    async fn create_user(transaction: &mut sqlx::Transaction<'_, sqlx::MySql>) -> (i32, Value) {
+        let user_id = create_new_test_user(&mut **transaction).await.unwrap();
+        let username = Message::new("UuidQuery", json!({ "id": user_id }))
+            .execute_on(&mut *transaction)
+            .await
+            .get_json()["username"]
+            .clone();
+        (user_id, username)
+    }
+
+    async fn create_comment(
+        thread_id: i32,
+        content: &str,
+        transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    ) -> Value {
+        Message::new(
+            "ThreadCreateCommentMutation",
+            json!({
+                "threadId": thread_id,
+                "userId": 1,
+                "content": content,
+                "subscribe": false,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut *transaction)
+        .await
+        .get_json()["id"]
+            .clone()
+    }
+
+    #[actix_rt::test]
+    async fn notifies_mentioned_user_and_lists_mention() {
+        let mut transaction = begin_transaction().await;
+        let (user_id, username) = create_user(&mut transaction).await;
+
+        let comment_id = create_comment(
+            17774,
+            &format!("@{} could you have a look?", username.as_str().unwrap()),
+            &mut transaction,
+        )
+        .await;
+
+        Message::new("MentionsQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["mentions"], 1);
+                assert_eq!(result["mentions"][0]["commentId"], comment_id);
+                assert_eq!(result["mentions"][0]["authorId"], 1);
+            });
+
+        Message::new("NotificationsQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["notifications"], 1);
+                assert_eq!(result["notifications"][0]["email"], false);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn notifies_mentioned_subscriber_only_once() {
+        let mut transaction = begin_transaction().await;
+        let (user_id, username) = create_user(&mut transaction).await;
+
+        let thread_id = Message::new(
+            "ThreadCreateThreadMutation",
+            json!({
+                "title": "Title",
+                "content": "This is content.",
+                "objectId": 1565,
+                "userId": user_id,
+                "subscribe": true,
+                "sendEmail": true,
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap() as i32;
+
+        create_comment(
+            thread_id,
+            &format!("Thanks @{}!", username.as_str().unwrap()),
+            &mut transaction,
+        )
+        .await;
+
+        Message::new("NotificationsQuery", json!({ "userId": user_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["notifications"], 1);
+                assert_eq!(result["notifications"][0]["email"], true);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn ignores_unknown_usernames() {
+        let mut transaction = begin_transaction().await;
+
+        create_comment(17774, "@no-user-with-this-name-exists", &mut transaction).await;
+
+        Message::new("MentionsQuery", json!({ "userId": 1 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with_body(json!({ "mentions": [] }));
+    }
+}
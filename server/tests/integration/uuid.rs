@@ -85,3 +85,213 @@ mod set_uuid_state_mutation {
         }
     }
 }
+
+mod uuid_paths_query {
+    use test_utils::{assert_eq, *};
+
+    fn last_ids(path: &Value, count: usize) -> Vec<Value> {
+        let nodes = path["nodes"].as_array().unwrap();
+        nodes[nodes.len() - count..]
+            .iter()
+            .map(|node| node["id"].clone())
+            .collect()
+    }
+
+    async fn create_topic(transaction: &mut sqlx::Transaction<'_, sqlx::MySql>) -> i32 {
+        Message::new(
+            "TaxonomyTermCreateMutation",
+            json!({
+                "parentId": 1394,
+                "name": "a topic",
+                "description": null,
+                "userId": 1,
+                "taxonomyType": "topic"
+            }),
+        )
+        .execute_on(transaction)
+        .await
+        .get_json()["id"]
+            .as_i64()
+            .unwrap() as i32
+    }
+
+    #[actix_rt::test]
+    async fn returns_path_of_taxonomy_term() {
+        let mut transaction = begin_transaction().await;
+        let topic = create_topic(&mut transaction).await;
+
+        Message::new("UuidPathsQuery", json!({ "id": topic }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["paths"], 1);
+
+                let path = &result["paths"][0];
+                assert_eq!(path["canonical"], true);
+                assert_eq!(last_ids(path, 2), vec![json!(1394), json!(topic)]);
+                assert_json_include!(
+                    actual: path["nodes"].as_array().unwrap().last().unwrap(),
+                    expected: json!({ "__typename": "TaxonomyTerm", "name": "a topic" })
+                );
+            });
+    }
+
+    #[actix_rt::test]
+    async fn returns_paths_of_course_page_via_its_course() {
+        Message::new("UuidPathsQuery", json!({ "id": 18521 }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                let paths = result["paths"].as_array().unwrap();
+                assert!(!paths.is_empty());
+                assert_eq!(
+                    paths
+                        .iter()
+                        .filter(|path| path["canonical"] == true)
+                        .count(),
+                    1
+                );
+
+                for path in paths {
+                    assert_eq!(last_ids(path, 2), vec![json!(18514), json!(18521)]);
+                    assert_eq!(path["nodes"][0]["__typename"], "TaxonomyTerm");
+                }
+            });
+    }
+
+    #[actix_rt::test]
+    async fn canonical_path_avoids_trashed_terms() {
+        let mut transaction = begin_transaction().await;
+        let trashed_topic = create_topic(&mut transaction).await;
+        let topic = create_topic(&mut transaction).await;
+
+        sqlx::query!("DELETE FROM term_taxonomy_entity WHERE entity_id = 1495")
+            .execute(&mut *transaction)
+            .await
+            .unwrap();
+
+        for taxonomy_term_id in [trashed_topic, topic] {
+            Message::new(
+                "TaxonomyCreateEntityLinksMutation",
+                json!({ "userId": 1, "entityIds": [1495], "taxonomyTermId": taxonomy_term_id }),
+            )
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok();
+        }
+
+        Message::new(
+            "UuidSetStateMutation",
+            json!({ "ids": [trashed_topic], "userId": 1, "trashed": true }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .should_be_ok();
+
+        Message::new("UuidPathsQuery", json!({ "id": 1495 }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["paths"], 2);
+
+                assert_eq!(result["paths"][0]["canonical"], false);
+                assert_eq!(
+                    last_ids(&result["paths"][0], 2),
+                    vec![json!(trashed_topic), json!(1495)]
+                );
+
+                assert_eq!(result["paths"][1]["canonical"], true);
+                assert_eq!(
+                    last_ids(&result["paths"][1], 2),
+                    vec![json!(topic), json!(1495)]
+                );
+            });
+    }
+
+    #[actix_rt::test]
+    async fn returns_paths_of_threads_via_their_object() {
+        let mut transaction = begin_transaction().await;
+
+        let thread_id = Message::new(
+            "ThreadCreateThreadMutation",
+            json!({
+                "title": "title",
+                "content": "content",
+                "objectId": 1495,
+                "userId": 1,
+                "subscribe": false,
+                "sendEmail": false,
+            }),
+        )
+        .execute_on(&mut transaction)
+        .await
+        .get_json()["id"]
+            .clone();
+
+        Message::new("UuidPathsQuery", json!({ "id": thread_id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                for path in result["paths"].as_array().unwrap() {
+                    assert_eq!(last_ids(path, 2), vec![json!(1495), thread_id.clone()]);
+                    assert_json_include!(
+                        actual: path["nodes"].as_array().unwrap().last().unwrap(),
+                        expected: json!({ "__typename": "Comment", "name": null })
+                    );
+                }
+            });
+    }
+
+    #[actix_rt::test]
+    async fn returns_single_path_for_pages() {
+        Message::new("UuidPathsQuery", json!({ "id": 16256 }))
+            .execute()
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["paths"], 1);
+                assert_json_include!(
+                    actual: &result["paths"][0],
+                    expected: json!({
+                        "canonical": true,
+                        "nodes": [{ "id": 16256, "__typename": "Page", "name": null }]
+                    })
+                );
+                assert_has_length(&result["paths"][0]["nodes"], 1);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn returns_path_of_page_revision_via_its_page() {
+        let mut transaction = begin_transaction().await;
+        let revision = sqlx::query!("SELECT id, page_repository_id FROM page_revision LIMIT 1")
+            .fetch_one(&mut *transaction)
+            .await
+            .unwrap();
+
+        Message::new("UuidPathsQuery", json!({ "id": revision.id }))
+            .execute_on(&mut transaction)
+            .await
+            .should_be_ok_with(|result| {
+                assert_has_length(&result["paths"], 1);
+                assert_json_include!(
+                    actual: &result["paths"][0],
+                    expected: json!({
+                        "canonical": true,
+                        "nodes": [
+                            { "id": revision.page_repository_id, "__typename": "Page" },
+                            { "id": revision.id, "__typename": "PageRevision" }
+                        ]
+                    })
+                );
+                assert_has_length(&result["paths"][0]["nodes"], 2);
+            });
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_uuid_does_not_exist() {
+        Message::new("UuidPathsQuery", json!({ "id": 999999 }))
+            .execute()
+            .await
+            .should_be_not_found();
+    }
+}
@@ -90,6 +90,14 @@ impl MessageResult {
         assert!(!json_body["reason"].as_str().unwrap().is_empty());
     }
 
+    pub fn should_be_bad_request_with<F>(self, assert_func: F)
+    where
+        F: FnOnce(Value),
+    {
+        assert_eq!(self.status, 400);
+        assert_func(self.get_json());
+    }
+
     pub fn get_json(self) -> Value {
         from_slice(&self.body).unwrap()
     }
@@ -253,6 +261,11 @@ pub async fn assert_event_revision_ok(
     });
 }
 
+pub const TEST_CONTENT: &str =
+    r#"{"plugin":"rows","state":[{"plugin":"text","state":"test content"}]}"#;
+pub const TEST_DESCRIPTION: &str =
+    r#"{"plugin":"rows","state":[{"plugin":"text","state":"test description"}]}"#;
+
 pub struct EntityTestWrapper<'a> {
     pub revision_type: EntityRevisionType,
     pub typename: EntityType,
@@ -266,8 +279,8 @@ pub struct EntityTestWrapper<'a> {
 impl EntityTestWrapper<'static> {
     pub fn fields(&self) -> HashMap<&str, &str> {
         let all_entity_fields: HashMap<&str, &str> = HashMap::from([
-            ("content", "test content"),
-            ("description", "test description"),
+            ("content", TEST_CONTENT),
+            ("description", TEST_DESCRIPTION),
             ("metaDescription", "test metaDescription"),
             ("metaTitle", "test metaTitle"),
             ("title", "test title"),
@@ -307,7 +320,7 @@ impl EntityTestWrapper<'static> {
                 parent_id: None,
                 own_field_keys: vec!["description", "title", "metaDescription"],
                 query_fields: Some(HashMap::from([
-                    ("content", "test description"),
+                    ("content", TEST_DESCRIPTION),
                     ("metaDescription", "test metaDescription"),
                     ("title", "test title"),
                 ])),
@@ -347,7 +360,7 @@ impl EntityTestWrapper<'static> {
                 parent_id: None,
                 own_field_keys: vec!["content", "cohesive"],
                 query_fields: Some(HashMap::from([
-                    ("content", "test content"),
+                    ("content", TEST_CONTENT),
                     // TODO: missing test due to mismatched type
                     // ("cohesive", true),
                 ])),
@@ -360,8 +373,8 @@ impl EntityTestWrapper<'static> {
                 parent_id: None,
                 own_field_keys: vec!["content", "title", "description"],
                 query_fields: Some(HashMap::from([
-                    ("url", "test content"),
-                    ("content", "test description"),
+                    ("url", TEST_CONTENT),
+                    ("content", TEST_DESCRIPTION),
                     ("title", "test title"),
                 ])),
                 taxonomy_term_id: Some(7),